@@ -5,11 +5,6 @@ pub(crate) trait OsStrExt: private::Sealed {
     /// The Utf8Error is guaranteed to have a valid UTF8 boundary
     /// in its `valid_up_to()`
     fn try_str(&self) -> Result<&str, std::str::Utf8Error>;
-    /// Returns `true` if the given pattern matches a sub-slice of
-    /// this string slice.
-    ///
-    /// Returns `false` if it does not.
-    fn contains(&self, needle: &str) -> bool;
     /// Returns the byte index of the first character of this string slice that
     /// matches the pattern.
     ///
@@ -27,9 +22,6 @@ pub(crate) trait OsStrExt: private::Sealed {
     ///
     /// Returns `false` if it does not.
     fn starts_with(&self, prefix: &str) -> bool;
-    /// An iterator over substrings of this string slice, separated by
-    /// characters matched by a pattern.
-    fn split<'s, 'n>(&'s self, needle: &'n str) -> Split<'s, 'n>;
     /// Splits the string on the first occurrence of the specified delimiter and
     /// returns prefix before delimiter and suffix after delimiter.
     fn split_once(&self, needle: &'_ str) -> Option<(&OsStr, &OsStr)>;
@@ -41,10 +33,6 @@ impl OsStrExt for OsStr {
         std::str::from_utf8(bytes)
     }
 
-    fn contains(&self, needle: &str) -> bool {
-        self.find(needle).is_some()
-    }
-
     fn find(&self, needle: &str) -> Option<usize> {
         let bytes = self.as_encoded_bytes();
         (0..=self.len().checked_sub(needle.len())?)
@@ -65,14 +53,6 @@ impl OsStrExt for OsStr {
         bytes.starts_with(prefix.as_bytes())
     }
 
-    fn split<'s, 'n>(&'s self, needle: &'n str) -> Split<'s, 'n> {
-        assert_ne!(needle, "");
-        Split {
-            haystack: Some(self),
-            needle,
-        }
-    }
-
     fn split_once(&self, needle: &'_ str) -> Option<(&OsStr, &OsStr)> {
         let start = self.find(needle)?;
         let end = start + needle.len();
@@ -97,32 +77,6 @@ mod private {
     impl Sealed for std::ffi::OsStr {}
 }
 
-pub struct Split<'s, 'n> {
-    haystack: Option<&'s OsStr>,
-    needle: &'n str,
-}
-
-impl<'s, 'n> Iterator for Split<'s, 'n> {
-    type Item = &'s OsStr;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let haystack = self.haystack?;
-        match haystack.split_once(self.needle) {
-            Some((first, second)) => {
-                if !haystack.is_empty() {
-                    debug_assert_ne!(haystack, second);
-                }
-                self.haystack = Some(second);
-                Some(first)
-            }
-            None => {
-                self.haystack = None;
-                Some(haystack)
-            }
-        }
-    }
-}
-
 /// Split an `OsStr`
 ///
 /// # Safety
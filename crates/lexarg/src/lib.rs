@@ -119,6 +119,10 @@ impl<'a> Parser<'a> {
     ///
     /// Options that are not valid unicode are transformed with replacement
     /// characters as by [`String::from_utf8_lossy`].
+    ///
+    /// Clustered short options, like `-qh`, are returned one at a time.  Calling
+    /// [`Parser::flag_value`] after one takes the rest of the cluster as its value, like `4` in
+    /// `-j4`.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<Arg<'a>> {
         // Always reset
@@ -238,6 +242,31 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Get the next option or positional argument without consuming it
+    ///
+    /// This lets a plugin parser support a flag with an optional value by only calling
+    /// [`Parser::flag_value`] when a [`Arg::Value`] follows.
+    ///
+    /// # Example
+    /// ```
+    /// let args = ["--color", "always", "--color", "--shout"];
+    /// let mut parser = lexarg::Parser::new(&args);
+    /// let mut colors = Vec::new();
+    /// while let Some(arg) = parser.next() {
+    ///     if arg == lexarg::Arg::Long("color") {
+    ///         let value = match parser.peek() {
+    ///             Some(lexarg::Arg::Value(_)) => parser.flag_value(),
+    ///             _ => None,
+    ///         };
+    ///         colors.push(value);
+    ///     }
+    /// }
+    /// assert_eq!(colors, [Some(std::ffi::OsStr::new("always")), None]);
+    /// ```
+    pub fn peek(&self) -> Option<Arg<'a>> {
+        self.clone().next()
+    }
+
     /// Get a flag's value
     ///
     /// This function should normally be called right after seeing a flag that expects a value;
@@ -476,6 +505,26 @@ mod tests {
         assert_eq!(p.next(), None);
     }
 
+    #[test]
+    fn test_peek() {
+        let mut p = Parser::new(&["-ab", "--foo=bar", "baz", "--", "-q"]);
+        assert_eq!(p.peek().unwrap(), Short('a'));
+        assert_eq!(p.next().unwrap(), Short('a'));
+        assert_eq!(p.peek().unwrap(), Short('b'));
+        assert_eq!(p.next().unwrap(), Short('b'));
+        assert_eq!(p.next().unwrap(), Long("foo"));
+        assert_eq!(p.peek().unwrap(), Unexpected(OsStr::new("bar")));
+        assert_eq!(p.flag_value().unwrap(), "bar");
+        assert_eq!(p.peek().unwrap(), Value(OsStr::new("baz")));
+        assert_eq!(p.next().unwrap(), Value(OsStr::new("baz")));
+        assert_eq!(p.peek().unwrap(), Escape);
+        assert_eq!(p.next().unwrap(), Escape);
+        assert_eq!(p.peek().unwrap(), Value(OsStr::new("-q")));
+        assert_eq!(p.next().unwrap(), Value(OsStr::new("-q")));
+        assert_eq!(p.peek(), None);
+        assert_eq!(p.next(), None);
+    }
+
     #[test]
     fn test_combined() {
         let mut p = Parser::new(&["-abc", "-fvalue", "-xfvalue"]);
@@ -490,6 +539,22 @@ mod tests {
         assert_eq!(p.next(), None);
     }
 
+    #[test]
+    fn test_clustered_flags_then_value() {
+        let mut p = Parser::new(&["-qh", "-j4", "-qj", "4", "-qj=4"]);
+        assert_eq!(p.next().unwrap(), Short('q'));
+        assert_eq!(p.next().unwrap(), Short('h'));
+        assert_eq!(p.next().unwrap(), Short('j'));
+        assert_eq!(p.flag_value().unwrap(), "4");
+        assert_eq!(p.next().unwrap(), Short('q'));
+        assert_eq!(p.next().unwrap(), Short('j'));
+        assert_eq!(p.flag_value().unwrap(), "4");
+        assert_eq!(p.next().unwrap(), Short('q'));
+        assert_eq!(p.next().unwrap(), Short('j'));
+        assert_eq!(p.flag_value().unwrap(), "4");
+        assert_eq!(p.next(), None);
+    }
+
     #[test]
     fn test_long() {
         let mut p = Parser::new(&["--foo", "--bar=qux", "--foobar=qux=baz"]);
@@ -17,78 +17,166 @@ use lexarg_error::Result;
 #[derive(Debug, Default)]
 pub struct TestOpts {
     pub list: bool,
+    /// Group `--list` by module, from an explicit `--format=pretty`
+    pub list_tree: bool,
+    /// Format of `--list`, instead of following `--format`
+    pub list_format: Option<ListFormat>,
+    /// Show which tests would be run, without running them
+    pub dry_run: bool,
     pub filters: Vec<String>,
+    /// Read exact test names to filter on from this file, `-` being stdin
+    ///
+    /// This is read by the harness.  Names from the file always match exactly, while `filters`
+    /// still follow `filter_exact`.
+    pub filter_file: Option<std::path::PathBuf>,
     pub filter_exact: bool,
+    /// Run tests matching every filter, rather than any
+    pub filter_all: bool,
     pub force_run_in_process: bool,
+    /// Run each test in its own process, like for panic=abort
+    pub run_in_subprocess: bool,
     pub exclude_should_panic: bool,
     pub run_ignored: RunIgnored,
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<std::path::PathBuf>,
+    /// Write the outcome of each test to this file as json
+    pub save_results: Option<std::path::PathBuf>,
+    /// Compare against outcomes from `save_results` of an earlier run
+    pub baseline: Option<std::path::PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
     pub format: OutputFormat,
+    /// Write `--format=json` to stdout while writing human-readable output to stderr
+    pub json_stderr_pretty: bool,
+    /// Also write a JUnit document to this file
+    pub junit_path: Option<std::path::PathBuf>,
+    /// Classname for `--format=junit` tests that aren't in a module, instead of `crate`
+    pub junit_classname: Option<String>,
+    /// Show how many tests have completed on stderr, when it is a terminal and stdout is
+    /// redirected
+    pub progress: bool,
+    /// With `--format=json`, report when each test started and completed, relative to the start
+    /// of the run
+    pub timeline: bool,
+    /// Before the test result, show how many tests passed and failed in each top-level module
+    pub subtotals: bool,
+    /// With `--format=json`, leave out the `discover-case` event for each test
+    ///
+    /// Consumers that need the full listing should not pass `--quiet`.
+    pub quiet: bool,
+    pub order: TestOrder,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
+    /// `None` means to use the available parallelism
     pub test_threads: Option<std::num::NonZeroUsize>,
     pub skip: Vec<String>,
+    /// Only run one shard of the tests, for splitting them across machines
+    pub partition: Option<Partition>,
+    /// Only run tests with any of these tags
+    pub include_tags: Vec<String>,
+    /// Don't run tests with any of these tags
+    pub exclude_tags: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// How many times to run each test, `None` meaning once
+    pub iterations: Option<std::num::NonZeroUsize>,
+    /// How many times to run and report each test, to flush out flaky ones
+    ///
+    /// Unlike `iterations`, each run is reported on its own, as `name #N`.  The runs of a test
+    /// stay together, as tests are ordered, including by `shuffle`, before being repeated.
+    pub repeat: Option<std::num::NonZeroUsize>,
+    /// How long each test has to run, see `State::check_deadline`
+    pub timeout: Option<std::time::Duration>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
     pub fail_fast: bool,
+    /// Fail when no tests are run or listed, like when filters have a typo
+    pub fail_on_empty: bool,
+    /// On Ctrl-C, stop starting tests and report those that finished
+    pub graceful_interrupt: bool,
     pub options: Options,
     pub allowed_unstable: Vec<String>,
+    /// Features from `allowed_unstable` that no parser registered, for the harness to warn about
+    ///
+    /// See [`TestOptsParseState::register_unstable`].
+    pub unknown_unstable: Vec<String>,
 }
 
 /// Whether ignored test should be run or not
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum RunIgnored {
     Yes,
+    #[default]
     No,
     /// Run only ignored tests
     Only,
 }
 
-impl Default for RunIgnored {
-    fn default() -> Self {
-        Self::No
-    }
-}
-
 /// Whether should console output be colored or not
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub enum ColorConfig {
+    #[default]
     AutoColor,
     AlwaysColor,
     NeverColor,
 }
 
-impl Default for ColorConfig {
-    fn default() -> Self {
-        Self::AutoColor
-    }
+/// Order to run tests in, unless shuffled
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TestOrder {
+    /// Sorted by name
+    #[default]
+    Name,
+    /// In the order the tests were declared
+    Declared,
 }
 
-/// Format of the test results output
+/// Format of `--list` output
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    /// `discover-case` events, like `--format=json`
+    Json,
+    /// Only the name of each test, one per line
+    Plain,
+    /// Each test with its kind, followed by the count
+    Terse,
+}
+
+/// A shard of the tests, from `--partition STRATEGY:INDEX/TOTAL`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+    pub strategy: PartitionStrategy,
+    /// Which shard to run, from `1` to `total`
+    pub index: usize,
+    pub total: usize,
+}
+
+/// How tests are assigned to a [`Partition`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PartitionStrategy {
+    /// Split the tests, sorted by name, into contiguous chunks of equal size
+    Count,
+    /// Assign each test by the hash of its name, so adding tests doesn't move the others
+    Hash,
+}
+
+/// Format of the test results output
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum OutputFormat {
     /// Verbose output
+    #[default]
     Pretty,
     /// Quiet output
     Terse,
     /// JSON output
     Json,
+    /// Indented JSON output, for reading by hand
+    JsonPretty,
     /// JUnit output
     Junit,
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        Self::Pretty
-    }
-}
-
 /// Structure with parameters for calculating test execution time.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct TestTimeOptions {
@@ -183,40 +271,152 @@ pub struct Options {
 
 pub const UNSTABLE_OPTIONS: &str = "unstable-options";
 
+/// Long flags recognized by [`TestOptsParseState::parse_next`], without the leading `--`
+///
+/// For suggesting a flag when one is mistyped.
+pub const LONG_FLAGS: &[&str] = &[
+    "include-ignored",
+    "ignored",
+    "force-run-in-process",
+    "run-in-subprocess",
+    "exclude-should-panic",
+    "test",
+    "bench",
+    "list",
+    "dry-run",
+    "logfile",
+    "save-results",
+    "baseline",
+    "nocapture",
+    "test-threads",
+    "skip",
+    "partition",
+    "include-tag",
+    "exclude-tag",
+    "fail-fast",
+    "no-fail-fast",
+    "fail-on-empty",
+    "graceful-interrupt",
+    "exact",
+    "filter-file",
+    "filter-all",
+    "color",
+    "no-color",
+    "order",
+    "quiet",
+    "format",
+    "list-format",
+    "json-stderr-pretty",
+    "progress",
+    "timeline",
+    "subtotals",
+    "junit-path",
+    "junit-classname",
+    "show-output",
+    "report-time",
+    "ensure-time",
+    "shuffle",
+    "shuffle-seed",
+    "timeout",
+    "iterations",
+    "repeat",
+];
+
 pub const OPTIONS_HELP: &str = r#"
 Options:
         --include-ignored 
                         Run ignored and not ignored tests
-        --ignored       Run only ignored tests
+        --ignored       Run only ignored tests (tests that decide to ignore
+                        themselves while running are never run)
         --force-run-in-process 
                         Forces tests to run in-process when panic=abort
+        --run-in-subprocess 
+                        Run each test in its own process so an abort only fails
+                        that test
         --exclude-should-panic 
                         Excludes tests marked as should_panic
         --test          Run tests and not benchmarks
         --bench         Run benchmarks instead of tests
-        --list          List all tests and benchmarks
+        --list          List all tests and benchmarks, grouped by module with
+                        --format=pretty
+        --dry-run       Show whether each test would run, be filtered out, or
+                        be ignored, without running any
         --logfile PATH  Write logs to the specified file
-        --nocapture     don't capture stdout/stderr of each task, allow
-                        printing directly
+        --save-results PATH
+                        Write the outcome of each test to PATH as json
+        --baseline PATH Mark tests that newly fail or were fixed since the
+                        run that wrote PATH with --save-results
+        --nocapture     Report each line a test writes as a case-output event,
+                        with --format=json and --run-in-subprocess; lines of
+                        parallel tests interleave
         --test-threads n_threads
-                        Number of threads used for running tests in parallel
+                        Number of threads used for running tests in parallel;
+                        0 = use the available parallelism (default);
+                        N% = use N percent of the available parallelism,
+                        rounded down but at least 1
         --skip FILTER   Skip tests whose names contain FILTER (this flag can
                         be used multiple times)
+        --partition count|hash:INDEX/TOTAL
+                        Only run shard INDEX of TOTAL (counting from 1):
+                        count = split tests sorted by name into equal chunks;
+                        hash = assign tests to shards by hashing their name
+        --include-tag TAG
+                        Only run tests tagged with TAG (this flag can be used
+                        multiple times to run tests with any of the tags)
+        --exclude-tag TAG
+                        Skip tests tagged with TAG (this flag can be used
+                        multiple times)
+        --fail-fast     Stop running tests after the first failure
+        --no-fail-fast  Run all tests regardless of failure (default)
+        --fail-on-empty Fail if no tests are run or listed, like when FILTER has
+                        a typo
+        --graceful-interrupt
+                        On Ctrl-C, stop starting tests and report those that
+                        finished; a second Ctrl-C exits right away
     -q, --quiet         Display one character per test instead of one line.
-                        Alias to --format=terse
+                        Alias to --format=terse, except with --format=json
+                        where it leaves out discovering each test
         --exact         Exactly match filters rather than by substring
+        --filter-file PATH
+                        Also run the tests named on each line of PATH, or of
+                        stdin for `-`, matching each name exactly
+        --filter-all    Only run tests matching every FILTER, rather than any
         --color auto|always|never
                         Configure coloring of output:
                         auto = colorize if stdout is a tty and tests are run
                         on serially (default);
                         always = always colorize output;
                         never = never colorize output;
-        --format pretty|terse|json|junit
+        --no-color      Alias to --color=never
+        --format pretty|terse|json|json-pretty|junit
                         Configure formatting of output:
                         pretty = Print verbose output;
                         terse = Display one character per test;
                         json = Output a json document;
+                        json-pretty = Output an indented json document;
                         junit = Output a JUnit document
+        --list-format json|plain|terse
+                        Configure formatting of --list, regardless of
+                        --format:
+                        json = Output discovered tests as json;
+                        plain = Print only the name of each test;
+                        terse = Print each test and whether it is a test or
+                        benchmark
+        --json-stderr-pretty
+                        Write json to stdout and human-readable output (see
+                        --format) to stderr
+        --junit-path PATH
+                        Also write a JUnit document to PATH, keeping --format
+                        for stdout
+        --junit-classname NAME
+                        Classname for --format=junit tests that aren't in a
+                        module (default: the suite name or `crate`)
+        --progress      Show how many tests have completed on stderr, when it
+                        is a terminal and stdout is redirected
+        --timeline      With --format=json, report when each test started and
+                        completed, in seconds since the run started
+        --subtotals     Before the test result, show how many tests passed and
+                        failed in each top-level module
         --show-output   Show captured stdout of successful tests
     -Z unstable-options Enable nightly-only flags:
                         unstable-options = Allow use of experimental features
@@ -242,6 +442,17 @@ Options:
                         `VARIABLE=WARN_TIME,CRITICAL_TIME`.
                         `CRITICAL_TIME` here means the limit that should not
                         be exceeded by test.
+        --order name|declared
+                        Order to run tests in, unless shuffled:
+                        name = sort tests by name (default);
+                        declared = run tests in the order they were declared
+        --timeout SECONDS
+                        Time each test has before cooperatively stopping
+        --iterations N  Run each test N times, failing if any run fails.
+                        With --report-time, shows the minimum, median, and
+                        maximum time of the runs
+        --repeat N      Run each test N times, reporting each run separately
+                        as `NAME #I` to flush out flaky tests
         --shuffle       Run tests in random order
         --shuffle-seed SEED
                         Run tests in random order; seed the random number
@@ -251,7 +462,9 @@ Options:
 pub const AFTER_HELP: &str = r#"
 The FILTER string is tested against the name of all tests, and only those
 tests whose names contain the filter are run. Multiple filter strings may
-be passed, which will run all tests matching any of the filters.
+be passed, which will run all tests matching any of the filters, or with
+--filter-all, only those matching every filter. Everything after `--` is
+treated as a filter, even if it looks like a flag.
 
 By default, all tests are run in parallel. This can be altered with the
 --test-threads flag or the RUST_TEST_THREADS environment variable when running
@@ -291,8 +504,19 @@ pub struct TestOptsParseState {
     opts: TestOpts,
     quiet: bool,
     format: Option<OutputFormat>,
+    order: Option<TestOrder>,
     include_ignored: bool,
     ignored: bool,
+    /// `--test-threads=0` was passed, overriding `RUST_TEST_THREADS`
+    test_threads_auto: bool,
+    /// `--test-threads=N%`, resolved against the available parallelism in `finish`
+    test_threads_percent: Option<usize>,
+    /// `-Z` features handled by other parsers, see [`TestOptsParseState::register_unstable`]
+    known_unstable: Vec<String>,
+    /// Act as if `-Zunstable-options` was passed, see [`TestOptsParseState::allow_unstable`]
+    allow_unstable: bool,
+    /// Used when `--format` isn't passed, see [`TestOptsParseState::default_format`]
+    default_format: Option<OutputFormat>,
 }
 
 impl TestOptsParseState {
@@ -300,7 +524,32 @@ impl TestOptsParseState {
         Default::default()
     }
 
+    /// Mark `-Z <feature>` as handled by another parser
+    ///
+    /// [`TestOptsParseState::finish`] reports `-Z` features that no parser registered in
+    /// [`TestOpts::unknown_unstable`], to catch typos.
+    pub fn register_unstable(&mut self, feature: impl Into<String>) {
+        self.known_unstable.push(feature.into());
+    }
+
+    /// Accept unstable options without `-Zunstable-options`
+    ///
+    /// This is for harnesses that aren't bound by Rust's stability policy.
+    pub fn allow_unstable(&mut self, yes: bool) {
+        self.allow_unstable = yes;
+    }
+
+    /// Use `format` unless `--format` is passed
+    ///
+    /// Unlike `--format`, this doesn't require `-Zunstable-options`.
+    pub fn default_format(&mut self, format: OutputFormat) {
+        self.default_format = Some(format);
+    }
+
     /// Check if `arg` is relevant to [`TestOpts`]
+    ///
+    /// [`Arg::Escape`] is consumed and the [`Arg::Value`]s that follow it are added as filters, so
+    /// `cargo test -- -- --foo` filters on `--foo`.
     pub fn parse_next<'a>(
         &mut self,
         parser: &mut lexarg::Parser<'a>,
@@ -314,6 +563,9 @@ impl TestOptsParseState {
             Arg::Long("force-run-in-process") => {
                 self.opts.force_run_in_process = true;
             }
+            Arg::Long("run-in-subprocess") => {
+                self.opts.run_in_subprocess = true;
+            }
             Arg::Long("exclude-should-panic") => {
                 self.opts.exclude_should_panic = true;
             }
@@ -326,6 +578,9 @@ impl TestOptsParseState {
             Arg::Long("list") => {
                 self.opts.list = true;
             }
+            Arg::Long("dry-run") => {
+                self.opts.dry_run = true;
+            }
             Arg::Long("logfile") => {
                 let path = parser
                     .flag_value()
@@ -338,15 +593,28 @@ impl TestOptsParseState {
             Arg::Long("test-threads") => {
                 let test_threads = parser
                     .flag_value()
-                    .ok_or_else(|| Error::msg("`--test-threads` requires a positive integer"))?
+                    .ok_or_else(|| Error::msg("`--test-threads` requires a number"))?
                     .to_str()
                     .ok_or_else(|| Error::msg("unsupported value"))?;
-                self.opts.test_threads = match test_threads.parse::<std::num::NonZeroUsize>() {
-                    Ok(n) => Some(n),
-                    Err(_) => {
-                        return Err(Error::msg("`--test-threads` must be a positive integer"));
-                    }
-                };
+                if let Some(percent) = test_threads.strip_suffix('%') {
+                    let percent = percent
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|p| *p != 0)
+                        .ok_or_else(|| {
+                            Error::msg("`--test-threads` percentage must be a positive integer")
+                        })?;
+                    self.test_threads_percent = Some(percent);
+                    self.opts.test_threads = None;
+                    self.test_threads_auto = false;
+                } else {
+                    let test_threads = test_threads.parse::<usize>().map_err(|_e| {
+                        Error::msg("`--test-threads` must be a non-negative integer")
+                    })?;
+                    self.test_threads_percent = None;
+                    self.opts.test_threads = std::num::NonZeroUsize::new(test_threads);
+                    self.test_threads_auto = self.opts.test_threads.is_none();
+                }
             }
             Arg::Long("skip") => {
                 let filter = parser
@@ -356,9 +624,46 @@ impl TestOptsParseState {
                     .ok_or_else(|| Error::msg("unsupported value"))?;
                 self.opts.skip.push(filter.to_owned());
             }
+            Arg::Long("include-tag") => {
+                let tag = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--include-tag` requires a value"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.opts.include_tags.push(tag.to_owned());
+            }
+            Arg::Long("exclude-tag") => {
+                let tag = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--exclude-tag` requires a value"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.opts.exclude_tags.push(tag.to_owned());
+            }
+            Arg::Long("fail-fast") => {
+                self.opts.fail_fast = true;
+            }
+            Arg::Long("no-fail-fast") => {
+                self.opts.fail_fast = false;
+            }
+            Arg::Long("fail-on-empty") => {
+                self.opts.fail_on_empty = true;
+            }
+            Arg::Long("timeline") => {
+                self.opts.timeline = true;
+            }
+            Arg::Long("subtotals") => {
+                self.opts.subtotals = true;
+            }
+            Arg::Long("graceful-interrupt") => {
+                self.opts.graceful_interrupt = true;
+            }
             Arg::Long("exact") => {
                 self.opts.filter_exact = true;
             }
+            Arg::Long("filter-all") => {
+                self.opts.filter_all = true;
+            }
             Arg::Long("color") => {
                 let color = parser
                     .flag_value()
@@ -376,17 +681,38 @@ impl TestOptsParseState {
                     }
                 };
             }
+            Arg::Long("no-color") => {
+                self.opts.color = ColorConfig::NeverColor;
+            }
+            Arg::Long("order") => {
+                let order = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--order` requires one of `name` or `declared`"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.order = Some(match order {
+                    "name" => TestOrder::Name,
+                    "declared" => TestOrder::Declared,
+                    _ => {
+                        return Err(Error::msg("`--order` accepts `name` or `declared`"));
+                    }
+                });
+            }
             Arg::Short('q') | Arg::Long("quiet") => {
-                self.format = None;
+                if !matches!(
+                    self.format,
+                    Some(OutputFormat::Json | OutputFormat::JsonPretty)
+                ) {
+                    self.format = None;
+                }
                 self.quiet = true;
             }
             Arg::Long("format") => {
-                self.quiet = false;
                 let format = parser
                     .flag_value()
                     .ok_or_else(|| {
                         Error::msg(
-                            "`--format` requires one of `pretty`, `terse`, `json`, or `junit`",
+                            "`--format` requires one of `pretty`, `terse`, `json`, `json-pretty`, or `junit`",
                         )
                     })?
                     .to_str()
@@ -395,14 +721,61 @@ impl TestOptsParseState {
                     "pretty" => OutputFormat::Pretty,
                     "terse" => OutputFormat::Terse,
                     "json" => OutputFormat::Json,
+                    "json-pretty" => OutputFormat::JsonPretty,
                     "junit" => OutputFormat::Junit,
                     _ => {
                         return Err(Error::msg(
-                            "`--format` accepts `pretty`, `terse`, `json`, or `junit`",
+                            "`--format` accepts `pretty`, `terse`, `json`, `json-pretty`, or `junit`",
+                        ));
+                    }
+                });
+                // `--quiet` only has a separate meaning for json
+                if !matches!(
+                    self.format,
+                    Some(OutputFormat::Json | OutputFormat::JsonPretty)
+                ) {
+                    self.quiet = false;
+                }
+            }
+            Arg::Long("list-format") => {
+                let format = parser
+                    .flag_value()
+                    .ok_or_else(|| {
+                        Error::msg("`--list-format` requires one of `json`, `plain`, or `terse`")
+                    })?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.opts.list_format = Some(match format {
+                    "json" => ListFormat::Json,
+                    "plain" => ListFormat::Plain,
+                    "terse" => ListFormat::Terse,
+                    _ => {
+                        return Err(Error::msg(
+                            "`--list-format` accepts `json`, `plain`, or `terse`",
                         ));
                     }
                 });
             }
+            Arg::Long("json-stderr-pretty") => {
+                self.opts.json_stderr_pretty = true;
+            }
+            Arg::Long("progress") => {
+                self.opts.progress = true;
+            }
+            Arg::Long("junit-path") => {
+                let path = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--junit-path` requires a path"))?;
+                self.opts.junit_path = Some(std::path::PathBuf::from(path));
+            }
+            Arg::Long("junit-classname") => {
+                let classname = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--junit-classname` requires a value"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.opts.junit_classname = Some(classname.to_owned());
+            }
             Arg::Long("show-output") => {
                 self.opts.options.display_output = true;
             }
@@ -448,7 +821,75 @@ impl TestOptsParseState {
                     .map_err(Error::msg)?;
                 self.opts.shuffle_seed = Some(seed);
             }
-            // All values are the same, whether escaped or not, so its a no-op
+            Arg::Long("timeout") => {
+                let timeout = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--timeout` requires a number of seconds"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?
+                    .parse::<f64>()
+                    .map_err(Error::msg)?;
+                let timeout = std::time::Duration::try_from_secs_f64(timeout).map_err(|_e| {
+                    Error::msg("`--timeout` must be a non-negative number of seconds")
+                })?;
+                self.opts.timeout = Some(timeout);
+            }
+            Arg::Long("save-results") => {
+                let path = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--save-results` requires a path"))?;
+                self.opts.save_results = Some(std::path::PathBuf::from(path));
+            }
+            Arg::Long("filter-file") => {
+                let path = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--filter-file` requires a path"))?;
+                self.opts.filter_file = Some(std::path::PathBuf::from(path));
+            }
+            Arg::Long("baseline") => {
+                let path = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--baseline` requires a path"))?;
+                self.opts.baseline = Some(std::path::PathBuf::from(path));
+            }
+            Arg::Long("partition") => {
+                let partition = parser
+                    .flag_value()
+                    .ok_or_else(|| {
+                        Error::msg(
+                            "`--partition` requires `count:INDEX/TOTAL` or `hash:INDEX/TOTAL`",
+                        )
+                    })?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?;
+                self.opts.partition = Some(parse_partition(partition)?);
+            }
+            Arg::Long("iterations") => {
+                let iterations = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--iterations` requires a number"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(std::num::NonZeroUsize::new)
+                    .ok_or_else(|| Error::msg("`--iterations` must be a positive integer"))?;
+                self.opts.iterations = Some(iterations);
+            }
+            Arg::Long("repeat") => {
+                let repeat = parser
+                    .flag_value()
+                    .ok_or_else(|| Error::msg("`--repeat` requires a number"))?
+                    .to_str()
+                    .ok_or_else(|| Error::msg("unsupported value"))?
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(std::num::NonZeroUsize::new)
+                    .ok_or_else(|| Error::msg("`--repeat` must be a positive integer"))?;
+                self.opts.repeat = Some(repeat);
+            }
+            // All values are the same, whether escaped or not, so its a no-op.
+            // `lexarg` reports everything after it as a value, making those filters.
             Arg::Escape => {}
             Arg::Value(filter) => {
                 let filter = filter
@@ -464,12 +905,28 @@ impl TestOptsParseState {
     }
 
     /// Finish parsing, resolving to [`TestOpts`]
-    pub fn finish(mut self) -> Result<TestOpts> {
-        let allow_unstable_options = self
+    ///
+    /// Flags take precedence over the `RUST_TEST_*` environment variables.  A shuffle seed, from
+    /// `--shuffle-seed` or `RUST_TEST_SHUFFLE_SEED`, always enables shuffling, even with
+    /// `RUST_TEST_SHUFFLE=0`.
+    pub fn finish(self) -> Result<TestOpts> {
+        self.finish_with_env(&|key| std::env::var(key).ok())
+    }
+
+    fn finish_with_env(mut self, env: &dyn Fn(&str) -> Option<String>) -> Result<TestOpts> {
+        let allow_unstable_options = self.allow_unstable
+            || self
+                .opts
+                .allowed_unstable
+                .iter()
+                .any(|f| f == UNSTABLE_OPTIONS);
+        self.opts.unknown_unstable = self
             .opts
             .allowed_unstable
             .iter()
-            .any(|f| f == UNSTABLE_OPTIONS);
+            .filter(|f| *f != UNSTABLE_OPTIONS && !self.known_unstable.contains(f))
+            .cloned()
+            .collect();
 
         if self.opts.force_run_in_process && !allow_unstable_options {
             return Err(Error::msg(
@@ -477,6 +934,67 @@ impl TestOptsParseState {
             ));
         }
 
+        if self.opts.run_in_subprocess && !allow_unstable_options {
+            return Err(Error::msg(
+                "`--run-in-subprocess` requires `-Zunstable-options`",
+            ));
+        }
+        if self.opts.dry_run && !allow_unstable_options {
+            return Err(Error::msg("`--dry-run` requires `-Zunstable-options`"));
+        }
+        if self.opts.junit_path.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--junit-path` requires `-Zunstable-options`"));
+        }
+        if self.opts.junit_classname.is_some() && !allow_unstable_options {
+            return Err(Error::msg(
+                "`--junit-classname` requires `-Zunstable-options`",
+            ));
+        }
+        if self.opts.fail_on_empty && !allow_unstable_options {
+            return Err(Error::msg(
+                "`--fail-on-empty` requires `-Zunstable-options`",
+            ));
+        }
+        if self.opts.timeline && !allow_unstable_options {
+            return Err(Error::msg("`--timeline` requires `-Zunstable-options`"));
+        }
+        if self.opts.subtotals && !allow_unstable_options {
+            return Err(Error::msg("`--subtotals` requires `-Zunstable-options`"));
+        }
+        if self.opts.graceful_interrupt && !allow_unstable_options {
+            return Err(Error::msg(
+                "`--graceful-interrupt` requires `-Zunstable-options`",
+            ));
+        }
+
+        if self.opts.save_results.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--save-results` requires `-Zunstable-options`"));
+        }
+
+        if self.opts.baseline.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--baseline` requires `-Zunstable-options`"));
+        }
+
+        if self.opts.filter_file.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--filter-file` requires `-Zunstable-options`"));
+        }
+        if self.opts.filter_all && !allow_unstable_options {
+            return Err(Error::msg("`--filter-all` requires `-Zunstable-options`"));
+        }
+
+        if self.opts.partition.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--partition` requires `-Zunstable-options`"));
+        }
+        if self.opts.iterations.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--iterations` requires `-Zunstable-options`"));
+        }
+        if self.opts.repeat.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--repeat` requires `-Zunstable-options`"));
+        }
+        if self.opts.timeout.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--timeout` requires `-Zunstable-options`"));
+        }
+
         if self.opts.exclude_should_panic && !allow_unstable_options {
             return Err(Error::msg(
                 "`--exclude-should-panic` requires `-Zunstable-options`",
@@ -487,9 +1005,9 @@ impl TestOptsParseState {
             return Err(Error::msg("`--shuffle` requires `-Zunstable-options`"));
         }
         if !self.opts.shuffle && allow_unstable_options {
-            self.opts.shuffle = match std::env::var("RUST_TEST_SHUFFLE") {
-                Ok(val) => &val != "0",
-                Err(_) => false,
+            self.opts.shuffle = match env("RUST_TEST_SHUFFLE") {
+                Some(val) => &val != "0",
+                None => false,
             };
         }
 
@@ -497,34 +1015,59 @@ impl TestOptsParseState {
             return Err(Error::msg("`--shuffle-seed` requires `-Zunstable-options`"));
         }
         if self.opts.shuffle_seed.is_none() && allow_unstable_options {
-            self.opts.shuffle_seed = match std::env::var("RUST_TEST_SHUFFLE_SEED") {
-                Ok(val) => match val.parse::<u64>() {
+            self.opts.shuffle_seed = match env("RUST_TEST_SHUFFLE_SEED") {
+                Some(val) => match val.parse::<u64>() {
                     Ok(n) => Some(n),
                     Err(_) => {
-                        return Err(Error::msg(
-                            "RUST_TEST_SHUFFLE_SEED is `{val}`, should be a number.",
-                        ));
+                        return Err(Error::msg(format!(
+                            "RUST_TEST_SHUFFLE_SEED is `{val}`, should be a number."
+                        )));
                     }
                 },
-                Err(_) => None,
+                None => None,
             };
         }
+        self.opts.shuffle |= self.opts.shuffle_seed.is_some();
 
         if !self.opts.nocapture {
-            self.opts.nocapture = match std::env::var("RUST_TEST_NOCAPTURE") {
-                Ok(val) => &val != "0",
-                Err(_) => false,
+            self.opts.nocapture = match env("RUST_TEST_NOCAPTURE") {
+                Some(val) => &val != "0",
+                None => false,
             };
         }
 
         if self.format.is_some() && !allow_unstable_options {
             return Err(Error::msg("`--format` requires `-Zunstable-options`"));
         }
-        if let Some(format) = self.format {
+        if let Some(format) = self.format.or(self.default_format) {
             self.opts.format = format;
         }
+        if self.order.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--order` requires `-Zunstable-options`"));
+        }
+        if let Some(order) = self.order {
+            self.opts.order = order;
+        }
+        if self.opts.list_format.is_some() && !allow_unstable_options {
+            return Err(Error::msg("`--list-format` requires `-Zunstable-options`"));
+        }
+        // Keep the default flat for tools parsing `--list`
+        self.opts.list_tree = self.opts.list
+            && self.opts.list_format.is_none()
+            && self.format == Some(OutputFormat::Pretty);
+        if self.opts.progress && !allow_unstable_options {
+            return Err(Error::msg("`--progress` requires `-Zunstable-options`"));
+        }
+        if self.opts.json_stderr_pretty && !allow_unstable_options {
+            return Err(Error::msg(
+                "`--json-stderr-pretty` requires `-Zunstable-options`",
+            ));
+        }
         if self.quiet {
-            self.opts.format = OutputFormat::Terse;
+            match self.opts.format {
+                OutputFormat::Json | OutputFormat::JsonPretty => self.opts.quiet = true,
+                _ => self.opts.format = OutputFormat::Terse,
+            }
         }
 
         self.opts.run_tests |= !self.opts.bench_benchmarks;
@@ -540,8 +1083,15 @@ impl TestOptsParseState {
             (false, false) => RunIgnored::No,
         };
 
-        if self.opts.test_threads.is_none() {
-            if let Ok(value) = std::env::var("RUST_TEST_THREADS") {
+        if let Some(percent) = self.test_threads_percent {
+            let available = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            self.opts.test_threads =
+                std::num::NonZeroUsize::new((available * percent / 100).max(1));
+        }
+        if self.opts.test_threads.is_none() && !self.test_threads_auto {
+            if let Some(value) = env("RUST_TEST_THREADS") {
                 self.opts.test_threads =
                     Some(value.parse::<std::num::NonZeroUsize>().map_err(|_e| {
                         Error::msg(format!(
@@ -556,6 +1106,34 @@ impl TestOptsParseState {
     }
 }
 
+/// Parse `STRATEGY:INDEX/TOTAL` for `--partition`
+fn parse_partition(value: &str) -> Result<Partition> {
+    let invalid = || {
+        Error::msg(format!(
+            "`--partition` expects `count:INDEX/TOTAL` or `hash:INDEX/TOTAL`, got `{value}`"
+        ))
+    };
+    let (strategy, shard) = value.split_once(':').ok_or_else(invalid)?;
+    let strategy = match strategy {
+        "count" => PartitionStrategy::Count,
+        "hash" => PartitionStrategy::Hash,
+        _ => return Err(invalid()),
+    };
+    let (index, total) = shard.split_once('/').ok_or_else(invalid)?;
+    let index = index.parse::<usize>().map_err(|_e| invalid())?;
+    let total = total.parse::<usize>().map_err(|_e| invalid())?;
+    if index == 0 || total < index {
+        return Err(Error::msg(format!(
+            "`--partition` index must be between 1 and {total}, got {index}"
+        )));
+    }
+    Ok(Partition {
+        strategy,
+        index,
+        total,
+    })
+}
+
 // FIXME: Copied from librustc_ast until linkage errors are resolved. Issue #47566
 fn is_nightly() -> bool {
     // Whether this is a feature-staged build, i.e., on the beta or stable channel
@@ -567,3 +1145,181 @@ fn is_nightly() -> bool {
 
     bootstrap || !disable_unstable_features
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(args: &[&str], env: &[(&str, &str)]) -> Result<TestOpts> {
+        finish_with(TestOptsParseState::new(), args, env)
+    }
+
+    fn finish_with(
+        mut state: TestOptsParseState,
+        args: &[&str],
+        env: &[(&str, &str)],
+    ) -> Result<TestOpts> {
+        let mut parser = lexarg::Parser::new(&args);
+        while let Some(arg) = parser.next() {
+            if let Some(arg) = state.parse_next(&mut parser, arg)? {
+                panic!("unhandled {arg:?}");
+            }
+        }
+        state.finish_with_env(&|key| {
+            env.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| (*v).to_owned())
+        })
+    }
+
+    #[test]
+    fn shuffle_from_env() {
+        let opts = finish(&["-Zunstable-options"], &[("RUST_TEST_SHUFFLE", "1")]).unwrap();
+        assert!(opts.shuffle);
+        assert_eq!(opts.shuffle_seed, None);
+
+        let opts = finish(&["-Zunstable-options"], &[("RUST_TEST_SHUFFLE", "0")]).unwrap();
+        assert!(!opts.shuffle);
+
+        let opts = finish(
+            &["-Zunstable-options", "--shuffle"],
+            &[("RUST_TEST_SHUFFLE", "0")],
+        )
+        .unwrap();
+        assert!(opts.shuffle);
+    }
+
+    #[test]
+    fn shuffle_seed_enables_shuffle() {
+        let opts = finish(
+            &["-Zunstable-options", "--shuffle-seed", "5"],
+            &[("RUST_TEST_SHUFFLE", "0")],
+        )
+        .unwrap();
+        assert!(opts.shuffle);
+        assert_eq!(opts.shuffle_seed, Some(5));
+
+        let opts = finish(
+            &["-Zunstable-options"],
+            &[("RUST_TEST_SHUFFLE", "0"), ("RUST_TEST_SHUFFLE_SEED", "7")],
+        )
+        .unwrap();
+        assert!(opts.shuffle);
+        assert_eq!(opts.shuffle_seed, Some(7));
+    }
+
+    #[test]
+    fn shuffle_seed_flag_over_env() {
+        let opts = finish(
+            &["-Zunstable-options", "--shuffle-seed", "5"],
+            &[("RUST_TEST_SHUFFLE_SEED", "7")],
+        )
+        .unwrap();
+        assert_eq!(opts.shuffle_seed, Some(5));
+    }
+
+    #[test]
+    fn shuffle_env_requires_unstable() {
+        let opts = finish(
+            &[],
+            &[("RUST_TEST_SHUFFLE", "1"), ("RUST_TEST_SHUFFLE_SEED", "7")],
+        )
+        .unwrap();
+        assert!(!opts.shuffle);
+        assert_eq!(opts.shuffle_seed, None);
+    }
+
+    #[test]
+    fn allow_unstable() {
+        let mut state = TestOptsParseState::new();
+        state.allow_unstable(true);
+        let opts = finish_with(state, &["--format", "json", "--shuffle"], &[]).unwrap();
+        assert_eq!(opts.format, OutputFormat::Json);
+        assert!(opts.shuffle);
+    }
+
+    #[test]
+    fn default_format() {
+        let mut state = TestOptsParseState::new();
+        state.default_format(OutputFormat::Json);
+        let opts = finish_with(state, &["-q"], &[]).unwrap();
+        assert_eq!(opts.format, OutputFormat::Json);
+        assert!(opts.quiet);
+
+        let mut state = TestOptsParseState::new();
+        state.default_format(OutputFormat::Json);
+        let opts = finish_with(state, &["-Zunstable-options", "--format", "terse"], &[]).unwrap();
+        assert_eq!(opts.format, OutputFormat::Terse);
+    }
+
+    #[test]
+    fn invalid_shuffle_seed_env() {
+        let err = finish(
+            &["-Zunstable-options"],
+            &[("RUST_TEST_SHUFFLE_SEED", "abc")],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RUST_TEST_SHUFFLE_SEED is `abc`, should be a number."
+        );
+    }
+
+    #[test]
+    fn invalid_test_threads_env() {
+        let err = finish(&[], &[("RUST_TEST_THREADS", "0")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RUST_TEST_THREADS is `0`, should be a positive integer."
+        );
+
+        let opts = finish(&["--test-threads", "2"], &[("RUST_TEST_THREADS", "0")]).unwrap();
+        assert_eq!(opts.test_threads, std::num::NonZeroUsize::new(2));
+    }
+
+    #[test]
+    fn test_threads_zero_is_auto() {
+        let opts = finish(&["--test-threads", "0"], &[("RUST_TEST_THREADS", "1")]).unwrap();
+        assert_eq!(opts.test_threads, None);
+    }
+
+    #[test]
+    fn ignored_flags_are_exclusive() {
+        let err = finish(&["--include-ignored", "--ignored"], &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`--include-ignored` and `--ignored` are mutually exclusive"
+        );
+    }
+
+    #[test]
+    fn unknown_unstable_features() {
+        let mut state = TestOptsParseState::new();
+        state.register_unstable("known");
+        let opts = finish_with(state, &["-Zunstable-options", "-Zknown", "-Ztypo"], &[]).unwrap();
+        assert_eq!(opts.unknown_unstable, ["typo"]);
+    }
+
+    #[test]
+    fn unstable_flags_require_unstable_options() {
+        for (args, flag) in [
+            (&["--shuffle"][..], "--shuffle"),
+            (&["--shuffle-seed", "1"][..], "--shuffle-seed"),
+            (&["--format", "json"][..], "--format"),
+            (&["--partition", "count:1/2"][..], "--partition"),
+            (&["--timeline"][..], "--timeline"),
+            (&["--order", "declared"][..], "--order"),
+            (&["--timeout", "1"][..], "--timeout"),
+            (&["--dry-run"][..], "--dry-run"),
+            (&["--progress"][..], "--progress"),
+            (&["--fail-on-empty"][..], "--fail-on-empty"),
+            (&["--junit-classname", "x"][..], "--junit-classname"),
+        ] {
+            let err = finish(args, &[]).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("`{flag}` requires `-Zunstable-options`")
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Cases written as `async fn`, driven by a runtime of the caller's choosing
+
+use crate::*;
+
+/// A [`Case`] whose body is a future
+///
+/// Add these with [`Harness::async_cases`].  Like with [`Case`], each case runs on a worker thread
+/// which blocks on the future with the [`Executor`].
+///
+/// Unlike [`Case`], everything but the name and body defaults to a plain test.
+pub trait AsyncCase: Send + Sync + 'static {
+    /// See [`Case::name`]
+    fn name(&self) -> &str;
+    fn kind(&self) -> TestKind {
+        Default::default()
+    }
+    /// See [`Case::mode`]
+    fn mode(&self) -> RunMode {
+        RunMode::Test
+    }
+    fn source(&self) -> Option<&Source> {
+        None
+    }
+    /// See [`Case::is_ignored`]
+    fn is_ignored(&self) -> Option<&str> {
+        None
+    }
+    /// See [`Case::tags`]
+    fn tags(&self) -> &[&str] {
+        &[]
+    }
+    /// See [`Case::describe`]
+    fn describe(&self) -> Option<&str> {
+        None
+    }
+    /// See [`Case::timeout`]
+    fn timeout(&self) -> Option<std::time::Duration> {
+        None
+    }
+    /// See [`Case::exclusive`]
+    fn exclusive(&self, _state: &State) -> bool {
+        false
+    }
+    /// See [`Case::main_thread`]
+    fn main_thread(&self) -> bool {
+        false
+    }
+
+    fn run(&self, state: &State) -> impl std::future::Future<Output = Result<(), RunError>> + Send;
+}
+
+/// Future of a running [`AsyncCase`]
+pub type RunFuture<'f> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), RunError>> + Send + 'f>>;
+
+/// Runtime to drive [`AsyncCase`]s on
+///
+/// This is called from several threads at once when running tests in parallel.
+pub trait Executor: Send + Sync + 'static {
+    /// Run `future` to completion
+    fn block_on(&self, future: RunFuture<'_>) -> Result<(), RunError>;
+}
+
+/// Run an [`AsyncCase`] as a [`Case`]
+pub(crate) struct BlockOn<C> {
+    pub(crate) case: C,
+    pub(crate) executor: std::sync::Arc<dyn Executor>,
+}
+
+impl<C: AsyncCase> Case for BlockOn<C> {
+    fn name(&self) -> &str {
+        self.case.name()
+    }
+    fn kind(&self) -> TestKind {
+        self.case.kind()
+    }
+    fn mode(&self) -> RunMode {
+        self.case.mode()
+    }
+    fn source(&self) -> Option<&Source> {
+        self.case.source()
+    }
+    fn is_ignored(&self) -> Option<&str> {
+        self.case.is_ignored()
+    }
+    fn tags(&self) -> &[&str] {
+        self.case.tags()
+    }
+    fn describe(&self) -> Option<&str> {
+        self.case.describe()
+    }
+    fn timeout(&self) -> Option<std::time::Duration> {
+        self.case.timeout()
+    }
+    fn exclusive(&self, state: &State) -> bool {
+        self.case.exclusive(state)
+    }
+    fn main_thread(&self) -> bool {
+        self.case.main_thread()
+    }
+
+    fn run(&self, state: &State) -> Result<(), RunError> {
+        self.executor.block_on(Box::pin(self.case.run(state)))
+    }
+}
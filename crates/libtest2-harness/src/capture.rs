@@ -0,0 +1,117 @@
+//! Buffer `tracing` events while a case runs, to report them if it fails
+
+/// Run `f`, returning the events it emitted on this thread, one per line
+///
+/// Events from threads spawned by `f` are not captured.
+pub(crate) fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    let logs = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+    let subscriber = Capture {
+        logs: logs.clone(),
+        next_span: std::sync::atomic::AtomicU64::new(1),
+    };
+    let result = tracing::subscriber::with_default(subscriber, f);
+    let logs = std::mem::take(&mut *logs.lock().unwrap_or_else(|e| e.into_inner()));
+    (result, logs)
+}
+
+struct Capture {
+    logs: std::sync::Arc<std::sync::Mutex<String>>,
+    next_span: std::sync::atomic::AtomicU64,
+}
+
+impl tracing::Subscriber for Capture {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        // Spans aren't reported, they only need to be distinct
+        let id = self
+            .next_span
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        tracing::span::Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let metadata = event.metadata();
+        let mut line = Line::default();
+        event.record(&mut line);
+
+        use std::fmt::Write as _;
+        let mut logs = self.logs.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(
+            logs,
+            "{} {}: {}{}",
+            metadata.level(),
+            metadata.target(),
+            line.message,
+            line.fields
+        );
+    }
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+/// An event rendered as `message key=value ...`
+#[derive(Default)]
+struct Line {
+    message: String,
+    fields: String,
+}
+
+impl tracing::field::Visit for Line {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write as _;
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_events() {
+        let (result, logs) = capture(|| {
+            tracing::info!("connecting");
+            let _span = tracing::info_span!("request").entered();
+            tracing::warn!(status = 404, path = "/missing", "not found");
+            5
+        });
+        assert_eq!(result, 5);
+        assert_eq!(
+            logs,
+            "INFO libtest2_harness::capture::tests: connecting
+WARN libtest2_harness::capture::tests: not found status=404 path=\"/missing\"
+"
+        );
+    }
+
+    #[test]
+    fn threads_are_separate() {
+        let (_, logs) = capture(|| {
+            std::thread::spawn(|| tracing::info!("elsewhere"))
+                .join()
+                .unwrap();
+        });
+        assert_eq!(logs, "");
+    }
+}
@@ -8,16 +8,51 @@ pub trait Case: Send + Sync + 'static {
     /// tests hierarchically it may.
     fn name(&self) -> &str;
     fn kind(&self) -> TestKind;
+    /// Whether this is a test or a benchmark
+    ///
+    /// Benchmarks are run as tests unless `--bench` is passed, in which case only benchmarks are
+    /// run.
+    fn mode(&self) -> RunMode {
+        RunMode::Test
+    }
     fn source(&self) -> Option<&Source>;
+    /// This case is ignored unless ignored tests are requested, with an optional reason
+    ///
+    /// `None` means the case might still ignore itself when run, see [`State::ignore`].
+    fn is_ignored(&self) -> Option<&str> {
+        None
+    }
+    /// Labels for selecting cases with `--include-tag` and `--exclude-tag`
+    fn tags(&self) -> &[&str] {
+        &[]
+    }
+    /// The scenario this case checks, for when the name is too terse to tell
+    ///
+    /// This is shown with failures and reported with `--format=json`.
+    fn describe(&self) -> Option<&str> {
+        None
+    }
+    /// How long this case has to run, instead of `--timeout`
+    ///
+    /// See [`State::deadline`].
+    fn timeout(&self) -> Option<std::time::Duration> {
+        None
+    }
     /// This case cannot run in parallel to other cases within this binary
     fn exclusive(&self, state: &State) -> bool;
+    /// This case must run on the thread that called [`Harness::main`]
+    ///
+    /// Unlike [`Case::exclusive`], other cases may still run in parallel to it.
+    fn main_thread(&self) -> bool {
+        false
+    }
 
     fn run(&self, state: &State) -> Result<(), RunError>;
 }
 
 /// Type of the test according to the [rust book](https://doc.rust-lang.org/cargo/guide/tests.html)
 /// conventions.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum TestKind {
     /// Unit-tests are expected to be in the `src` folder of the crate.
     UnitTest,
@@ -27,15 +62,10 @@ pub enum TestKind {
     DocTest,
     /// Tests for the sources that don't follow the project layout convention
     /// (e.g. tests in raw `main.rs` compiled by calling `rustc --test` directly).
+    #[default]
     Unknown,
 }
 
-impl Default for TestKind {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Source {
@@ -49,12 +79,27 @@ pub enum Source {
     Path(std::path::PathBuf),
 }
 
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rust {
+                source_file,
+                start_line,
+                start_col,
+                ..
+            } => write!(f, "{}:{start_line}:{start_col}", source_file.display()),
+            Self::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
 pub type RunResult = Result<(), RunError>;
 
 #[derive(Debug)]
 pub struct RunError {
     status: notify::RunStatus,
     cause: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    location: Option<String>,
 }
 
 impl RunError {
@@ -62,6 +107,7 @@ impl RunError {
         Self {
             status: notify::RunStatus::Failed,
             cause: Some(Box::new(cause)),
+            location: None,
         }
     }
 
@@ -69,10 +115,29 @@ impl RunError {
         Self::with_cause(Message(cause.to_string()))
     }
 
+    /// The case could not be run, like from a missing fixture
+    ///
+    /// This is reported separately from the case failing.
+    pub fn error(cause: impl std::fmt::Display) -> Self {
+        Self {
+            status: notify::RunStatus::Errored,
+            cause: Some(Box::new(Message(cause.to_string()))),
+            location: None,
+        }
+    }
+
+    /// Fail with the difference between what was `expected` and the `actual` value
+    ///
+    /// The values are compared line-by-line when reporting the failure.
+    pub fn diff(expected: impl std::fmt::Display, actual: impl std::fmt::Display) -> Self {
+        Self::with_cause(diff::Diff::new(expected.to_string(), actual.to_string()))
+    }
+
     pub(crate) fn ignore() -> Self {
         Self {
             status: notify::RunStatus::Ignored,
             cause: None,
+            location: None,
         }
     }
 
@@ -80,16 +145,63 @@ impl RunError {
         Self {
             status: notify::RunStatus::Ignored,
             cause: Some(Box::new(Message(reason))),
+            location: None,
+        }
+    }
+
+    pub(crate) fn skip(reason: String) -> Self {
+        Self {
+            status: notify::RunStatus::Skipped,
+            cause: Some(Box::new(Message(reason))),
+            location: None,
         }
     }
 
+    /// Report the failure as happening at `line` of `path`
+    ///
+    /// This is for failures outside of the case's own code, like at a line of a data file that a
+    /// generated case checks.  It is reported instead of [`Case::source`].
+    pub fn at(mut self, path: impl AsRef<std::path::Path>, line: usize) -> Self {
+        self.location = Some(format!("{}:{line}", path.as_ref().display()));
+        self
+    }
+
     pub(crate) fn status(&self) -> notify::RunStatus {
         self.status
     }
 
-    pub(crate) fn cause(&self) -> Option<&(dyn std::error::Error + Send + Sync)> {
+    pub(crate) fn cause(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
         self.cause.as_ref().map(|b| b.as_ref())
     }
+
+    /// The cause followed by its [`source`][std::error::Error::source]s, like `anyhow`
+    pub(crate) fn message(&self) -> Option<String> {
+        use std::fmt::Write as _;
+
+        let cause = self.cause()?;
+        let mut message = cause.to_string();
+        let sources = std::iter::successors(cause.source(), |e| e.source()).collect::<Vec<_>>();
+        if !sources.is_empty() {
+            message.push_str("\n\nCaused by:");
+            for (i, source) in sources.iter().enumerate() {
+                let _ = if sources.len() == 1 {
+                    write!(message, "\n    {source}")
+                } else {
+                    write!(message, "\n    {i}: {source}")
+                };
+            }
+        }
+        Some(message)
+    }
+
+    pub(crate) fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    pub(crate) fn with_location(mut self, location: Option<String>) -> Self {
+        self.location = location;
+        self
+    }
 }
 
 impl<E> From<E> for RunError
@@ -0,0 +1,95 @@
+use crate::*;
+
+/// What [`Harness::run`] found and, unless only listing, how the cases turned out
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Conclusion {
+    /// Names of the cases selected to run, in the order they are (or would be) run
+    ///
+    /// With `--list`, these are the cases that were listed.
+    pub cases: Vec<String>,
+    /// Number of cases excluded by filters
+    pub num_filtered_out: usize,
+    /// Whether the cases were run, rather than only listed with `--list` or `--dry-run`
+    pub ran: bool,
+    pub num_passed: usize,
+    pub num_failed: usize,
+    /// Number of cases that could not be run, see [`RunError::error`]
+    pub num_errored: usize,
+    pub num_ignored: usize,
+    pub num_skipped: usize,
+    /// The run was stopped early by Ctrl-C, from `--graceful-interrupt`
+    pub interrupted: bool,
+    /// How each case that was run turned out, in the order they completed
+    pub outcomes: Vec<CaseOutcome>,
+}
+
+impl Conclusion {
+    /// Whether any case failed or could not be run
+    pub fn has_failed(&self) -> bool {
+        0 < self.num_failed || 0 < self.num_errored
+    }
+
+    pub(crate) fn record(&mut self, event: &notify::Event) {
+        match event {
+            notify::Event::DiscoverCase {
+                name, run: true, ..
+            } => {
+                self.cases.push(name.clone());
+            }
+            notify::Event::DiscoverComplete {
+                num_filtered_out, ..
+            } => {
+                self.num_filtered_out = *num_filtered_out;
+            }
+            notify::Event::CaseComplete {
+                name,
+                status,
+                message,
+                ..
+            } => {
+                match status {
+                    Some(RunStatus::Ignored) => self.num_ignored += 1,
+                    Some(RunStatus::Skipped) => self.num_skipped += 1,
+                    Some(RunStatus::Failed) => self.num_failed += 1,
+                    Some(RunStatus::Errored) => self.num_errored += 1,
+                    None => self.num_passed += 1,
+                }
+                self.outcomes.push(CaseOutcome {
+                    name: name.clone(),
+                    status: *status,
+                    message: message.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How a case turned out, see [`Conclusion::outcomes`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CaseOutcome {
+    pub name: String,
+    /// `None` when the case passed
+    pub status: Option<RunStatus>,
+    /// Why the case failed, was ignored, or was skipped
+    pub message: Option<String>,
+}
+
+/// Record a [`Conclusion`] from the events passing through to `notifier`
+pub(crate) struct ConcludingNotifier<'n> {
+    pub(crate) notifier: &'n mut dyn notify::Notifier,
+    pub(crate) conclusion: Conclusion,
+}
+
+impl notify::Notifier for ConcludingNotifier<'_> {
+    fn threaded(&mut self, yes: bool) {
+        self.notifier.threaded(yes);
+    }
+
+    fn notify(&mut self, event: &notify::Event) -> std::io::Result<()> {
+        self.conclusion.record(event);
+        self.notifier.notify(event)
+    }
+}
@@ -0,0 +1,161 @@
+/// Expected vs actual values of a failed case, see [`RunError::diff`][crate::RunError::diff]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff {
+    expected: String,
+    actual: String,
+}
+
+impl Diff {
+    pub(crate) fn new(expected: String, actual: String) -> Self {
+        Self { expected, actual }
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+
+    /// Line-based diff, using the longest common subsequence
+    ///
+    /// Past [`MAX_LCS_CELLS`], the changed lines are shown as removed and then added, rather than
+    /// interleaved.
+    pub(crate) fn lines(&self) -> Vec<DiffLine<'_>> {
+        let expected = self.expected.lines().collect::<Vec<_>>();
+        let actual = self.actual.lines().collect::<Vec<_>>();
+
+        // Only the lines between a common prefix and suffix need the table
+        let prefix = expected
+            .iter()
+            .zip(&actual)
+            .take_while(|(e, a)| e == a)
+            .count();
+        let suffix = expected[prefix..]
+            .iter()
+            .rev()
+            .zip(actual[prefix..].iter().rev())
+            .take_while(|(e, a)| e == a)
+            .count();
+        let changed_expected = &expected[prefix..expected.len() - suffix];
+        let changed_actual = &actual[prefix..actual.len() - suffix];
+
+        let mut lines = Vec::with_capacity(expected.len().max(actual.len()));
+        lines.extend(expected[..prefix].iter().map(|l| DiffLine::Context(l)));
+        let cells = (changed_expected.len() + 1).saturating_mul(changed_actual.len() + 1);
+        if cells <= MAX_LCS_CELLS {
+            lcs_lines(changed_expected, changed_actual, &mut lines);
+        } else {
+            lines.extend(changed_expected.iter().map(|l| DiffLine::Expected(l)));
+            lines.extend(changed_actual.iter().map(|l| DiffLine::Actual(l)));
+        }
+        lines.extend(
+            expected[expected.len() - suffix..]
+                .iter()
+                .map(|l| DiffLine::Context(l)),
+        );
+        lines
+    }
+}
+
+/// Largest longest-common-subsequence table to build, to bound memory on huge values
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+fn lcs_lines<'s>(expected: &[&'s str], actual: &[&'s str], lines: &mut Vec<DiffLine<'s>>) {
+    // `lcs[i][j]` is the length of the LCS of `expected[i..]` and `actual[j..]`
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() && j < actual.len() {
+        if expected[i] == actual[j] {
+            lines.push(DiffLine::Context(expected[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Expected(expected[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Actual(actual[j]));
+            j += 1;
+        }
+    }
+    lines.extend(expected[i..].iter().map(|l| DiffLine::Expected(l)));
+    lines.extend(actual[j..].iter().map(|l| DiffLine::Actual(l)));
+}
+
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "--- expected")?;
+        write!(f, "+++ actual")?;
+        for line in self.lines() {
+            writeln!(f)?;
+            write!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diff {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum DiffLine<'s> {
+    Context(&'s str),
+    Expected(&'s str),
+    Actual(&'s str),
+}
+
+impl std::fmt::Display for DiffLine<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Context(line) => write!(f, " {line}"),
+            Self::Expected(line) => write!(f, "-{line}"),
+            Self::Actual(line) => write!(f, "+{line}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_line() {
+        let diff = Diff::new("a\nb\nc".to_owned(), "a\nB\nc\nd".to_owned());
+        assert_eq!(
+            diff.to_string(),
+            "--- expected
++++ actual
+ a
+-b
++B
+ c
++d"
+        );
+    }
+
+    #[test]
+    fn huge_change() {
+        let expected = (0..3000).map(|i| format!("e{i}\n")).collect::<String>();
+        let actual = (0..3000).map(|i| format!("a{i}\n")).collect::<String>();
+        let diff = Diff::new(
+            format!("same\n{expected}same"),
+            format!("same\n{actual}same"),
+        );
+        let lines = diff.lines();
+        assert_eq!(lines.len(), 6002);
+        assert_eq!(lines[0], DiffLine::Context("same"));
+        assert_eq!(lines[1], DiffLine::Expected("e0"));
+        assert_eq!(lines[3001], DiffLine::Actual("a0"));
+        assert_eq!(lines[6001], DiffLine::Context("same"));
+    }
+}
@@ -4,18 +4,106 @@ use crate::*;
 
 pub struct Harness {
     raw: Vec<std::ffi::OsString>,
+    opts: Option<libtest_lexarg::TestOpts>,
     cases: Vec<Box<dyn Case>>,
+    sources: Vec<CaseSource>,
+    filters: Vec<Filter>,
+    suite_name: Option<String>,
+    failure_exit_code: i32,
+    shuffle_seed: Option<u64>,
+    shuffle_with: Option<Shuffle>,
+    allow_unstable: bool,
+    default_format: Option<OutputFormat>,
+    error: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
+type Filter = Box<dyn Fn(&dyn Case) -> bool>;
+type CaseSource = Box<dyn Iterator<Item = Box<dyn Case>>>;
+type Shuffle = Box<dyn Fn(u64, &mut [Box<dyn Case>])>;
+
 impl Harness {
     pub fn with_args(args: impl IntoIterator<Item = impl Into<std::ffi::OsString>>) -> Self {
         let raw = args.into_iter().map(|s| s.into()).collect::<Vec<_>>();
-        Self { raw, cases: vec![] }
+        Self {
+            raw,
+            opts: None,
+            cases: vec![],
+            sources: vec![],
+            filters: vec![],
+            suite_name: None,
+            failure_exit_code: ERROR_EXIT_CODE,
+            shuffle_seed: None,
+            shuffle_with: None,
+            allow_unstable: false,
+            default_format: None,
+            error: None,
+        }
     }
 
+    /// Parse arguments from the process
+    ///
+    /// Arguments from the `LIBTEST2_ARGS` environment variable are inserted before the
+    /// command-line arguments, allowing the command-line to override them.  They are separated by
+    /// whitespace, which can be kept in an argument by quoting it with `'` or `"`, or escaping it
+    /// with `\`.
     pub fn with_env() -> Self {
-        let raw = std::env::args_os().collect::<Vec<_>>();
-        Self { raw, cases: vec![] }
+        let mut args = std::env::args_os();
+        let bin = args.next();
+        let (defaults, error) = match std::env::var("LIBTEST2_ARGS") {
+            Ok(defaults) => match split_args(&defaults) {
+                Ok(defaults) => (defaults, None),
+                Err(err) => (
+                    vec![],
+                    Some(format!("error: invalid `LIBTEST2_ARGS`: {err}")),
+                ),
+            },
+            Err(std::env::VarError::NotPresent) => (vec![], None),
+            Err(std::env::VarError::NotUnicode(_)) => (
+                vec![],
+                Some("error: `LIBTEST2_ARGS` is not valid UTF-8".to_owned()),
+            ),
+        };
+        let raw = bin
+            .into_iter()
+            .chain(defaults.into_iter().map(std::ffi::OsString::from))
+            .chain(args)
+            .collect::<Vec<_>>();
+        Self {
+            raw,
+            opts: None,
+            cases: vec![],
+            sources: vec![],
+            filters: vec![],
+            suite_name: None,
+            failure_exit_code: ERROR_EXIT_CODE,
+            shuffle_seed: None,
+            shuffle_with: None,
+            allow_unstable: false,
+            default_format: None,
+            error: error.map(Into::into),
+        }
+    }
+
+    /// Use `opts` rather than parsing arguments
+    ///
+    /// Unlike with parsed arguments, the `RUST_TEST_*` environment variables are not consulted and
+    /// `-Zunstable-options` is not required.
+    pub fn with_opts(opts: libtest_lexarg::TestOpts) -> Self {
+        let raw = std::env::args_os().take(1).collect::<Vec<_>>();
+        Self {
+            raw,
+            opts: Some(opts),
+            cases: vec![],
+            sources: vec![],
+            filters: vec![],
+            suite_name: None,
+            failure_exit_code: ERROR_EXIT_CODE,
+            shuffle_seed: None,
+            shuffle_with: None,
+            allow_unstable: false,
+            default_format: None,
+            error: None,
+        }
     }
 
     pub fn case(mut self, case: impl Case + 'static) -> Self {
@@ -30,49 +118,340 @@ impl Harness {
         self
     }
 
-    pub fn main(mut self) -> ! {
-        let mut parser = cli::Parser::new(&self.raw);
-        let opts = parse(&mut parser).unwrap_or_else(|err| {
+    /// Add cases written as `async fn`, driven by `executor`
+    #[cfg(feature = "async")]
+    pub fn async_cases(
+        mut self,
+        executor: impl Executor,
+        cases: impl IntoIterator<Item = impl AsyncCase>,
+    ) -> Self {
+        let executor: std::sync::Arc<dyn Executor> = std::sync::Arc::new(executor);
+        for case in cases {
+            self.cases.push(Box::new(async_case::BlockOn {
+                case,
+                executor: executor.clone(),
+            }));
+        }
+        self
+    }
+
+    /// Name this suite in the output
+    ///
+    /// This distinguishes the output of suites that are run together, like when embedding several
+    /// suites in one binary or aggregating `--format=json` from several binaries.
+    pub fn suite_name(mut self, name: impl Into<String>) -> Self {
+        self.suite_name = Some(name.into());
+        self
+    }
+
+    /// Exit with `code` when a case fails
+    ///
+    /// Errors in the harness itself are unaffected, see [`Harness::main`].
+    pub fn failure_exit_code(mut self, code: i32) -> Self {
+        self.failure_exit_code = code;
+        self
+    }
+
+    /// Shuffle the cases with `seed`, as if `--shuffle-seed` was passed
+    ///
+    /// `--shuffle-seed` takes precedence.
+    pub fn shuffle_seed(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
+    /// Reorder the cases with `shuffle` instead of the built-in shuffle
+    ///
+    /// `shuffle` is called with the seed when shuffling, like from `--shuffle` or
+    /// [`Harness::shuffle_seed`], and should order the cases the same way for the same seed.
+    pub fn shuffle_with(mut self, shuffle: impl Fn(u64, &mut [Box<dyn Case>]) + 'static) -> Self {
+        self.shuffle_with = Some(Box::new(shuffle));
+        self
+    }
+
+    /// Accept unstable options, like `--format`, without `-Zunstable-options`
+    ///
+    /// The `-Z` gate is for consistency with Rust's own test harness, which harnesses that aren't
+    /// bound by Rust's stability policy can opt out of.
+    pub fn allow_unstable(mut self, yes: bool) -> Self {
+        self.allow_unstable = yes;
+        self
+    }
+
+    /// Report results in `format` unless `--format` is passed
+    ///
+    /// Unlike `--format`, this doesn't require `-Zunstable-options`.
+    pub fn default_format(mut self, format: OutputFormat) -> Self {
+        self.default_format = Some(format);
+        self
+    }
+
+    /// Only run cases that `predicate` accepts
+    ///
+    /// This is applied after the command-line filters, like `--skip`.  Cases rejected by
+    /// `predicate` are reported as filtered out.  When called multiple times, a case must be
+    /// accepted by every predicate.
+    pub fn filter(mut self, predicate: impl Fn(&dyn Case) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Add cases that are produced lazily
+    ///
+    /// Cases are pulled from `source` while discovering tests and those that are filtered out
+    /// are dropped right away, so large generated suites don't need to be held in memory.
+    /// Filtered out cases are reported before the other cases.
+    pub fn case_source(mut self, source: impl Iterator<Item = Box<dyn Case>> + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Add cases that may have failed to be constructed
+    ///
+    /// The first error stops adding cases and is reported by [`Harness::main`].
+    pub fn try_cases<C, E>(mut self, cases: impl IntoIterator<Item = Result<C, E>>) -> Self
+    where
+        C: Case + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        for case in cases {
+            match case {
+                Ok(case) => {
+                    self.cases.push(Box::new(case));
+                }
+                Err(err) => {
+                    self.error = Some(err.into());
+                    break;
+                }
+            }
+        }
+        self
+    }
+
+    /// Add cases from setup that can fail, like reading a directory of fixtures
+    ///
+    /// `setup` is run right away.  An error means the suite can't be discovered, so it is reported
+    /// by [`Harness::main`] without running anything, rather than as a failing case.  Problems with
+    /// an individual case should instead be returned from [`Case::run`].
+    pub fn try_add<I, E>(mut self, setup: impl FnOnce() -> Result<I, E>) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Case + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        match setup() {
+            Ok(cases) => self.cases(cases),
+            Err(err) => {
+                self.error = Some(err.into());
+                self
+            }
+        }
+    }
+
+    /// Run the cases and exit
+    ///
+    /// When the `LIBTEST2_RESULT_LINE` environment variable is set, human-readable output ends
+    /// with a line like `::libtest2:result passed=1 failed=0 ignored=0 ...` whose format will not
+    /// change.
+    ///
+    /// With `--graceful-interrupt`, Ctrl-C stops new cases from starting and, once the running
+    /// ones finish, the results so far are reported before exiting with code 130.
+    ///
+    /// A failing case exits with `101`, unless changed by [`Harness::failure_exit_code`].  Failing
+    /// to write the results while running, like to stdout, `--logfile` or `--save-results`, also
+    /// exits with `101`.  Other errors, like invalid arguments, exit with `1`.
+    pub fn main(self) -> ! {
+        let failure_exit_code = self.failure_exit_code;
+        let (conclusion, opts) = self.execute(None).unwrap_or_else(|err| {
             eprintln!("{}", err);
-            std::process::exit(1)
+            let code = if err.is::<RunIoError>() {
+                ERROR_EXIT_CODE
+            } else {
+                1
+            };
+            std::process::exit(code)
         });
 
+        if conclusion.interrupted {
+            eprintln!("error: interrupted, remaining tests were not run");
+            std::process::exit(interrupt::EXIT_CODE)
+        }
+        if conclusion.has_failed() {
+            std::process::exit(failure_exit_code)
+        }
+        // Also for `--list`, to catch a suite that found no tests at all
+        if conclusion.cases.is_empty() && opts.fail_on_empty {
+            if opts.filters.is_empty() {
+                eprintln!("error: no tests to run");
+            } else {
+                let filters = opts
+                    .filters
+                    .iter()
+                    .map(|f| format!("`{f}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                eprintln!("error: no tests matched filter {filters}");
+            }
+            std::process::exit(failure_exit_code)
+        }
+
+        std::process::exit(0)
+    }
+
+    /// Run the cases, returning what happened instead of exiting
+    ///
+    /// With `--list` or `--dry-run`, the [`Conclusion`] only reports which cases were selected.
+    ///
+    /// `--help` still prints and exits, as does a child process from `--run-in-subprocess`.
+    pub fn run(self) -> Result<Conclusion, Box<dyn std::error::Error + Send + Sync>> {
+        self.execute(None).map(|(conclusion, _)| conclusion)
+    }
+
+    /// Run the cases, returning every [`Event`] instead of writing any output
+    ///
+    /// This tests [`Case`] implementations in-process, without a test binary of their own:
+    ///
+    /// ```
+    /// # use libtest2_harness::*;
+    /// # fn check(case: impl Case + 'static) {
+    /// let events = Harness::with_args(["test"]).case(case).run_to_events().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// `--format` is ignored.  Otherwise, this behaves like [`Harness::run`].
+    pub fn run_to_events(self) -> Result<Vec<Event>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut events = Vec::new();
+        self.execute(Some(&mut events))?;
+        Ok(events)
+    }
+
+    /// Run the cases, reporting to `events` rather than the output from `--format` when set
+    fn execute(
+        mut self,
+        events: Option<&mut Vec<Event>>,
+    ) -> Result<(Conclusion, libtest_lexarg::TestOpts), Box<dyn std::error::Error + Send + Sync>>
+    {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let opts = match self.opts.take() {
+            Some(opts) => opts,
+            None => {
+                let mut parser = cli::Parser::new(&self.raw);
+                let mut test_opts = libtest_lexarg::TestOptsParseState::new();
+                test_opts.allow_unstable(self.allow_unstable);
+                if let Some(format) = self.default_format {
+                    test_opts.default_format(format);
+                }
+                parse(&mut parser, test_opts).map_err(|err| err.to_string())?
+            }
+        };
+        let opts = resolve(opts);
+        for feature in &opts.unknown_unstable {
+            eprintln!("warning: unrecognized `-Z {feature}` feature");
+        }
+
         match opts.color {
-            libtest_lexarg::ColorConfig::AutoColor => anstream::ColorChoice::Auto,
+            libtest_lexarg::ColorConfig::AutoColor => auto_color(),
             libtest_lexarg::ColorConfig::AlwaysColor => anstream::ColorChoice::Always,
             libtest_lexarg::ColorConfig::NeverColor => anstream::ColorChoice::Never,
         }
         .write_global();
 
-        let mut notifier = notifier(&opts).unwrap_or_else(|err| {
-            eprintln!("{}", err);
-            std::process::exit(1)
-        });
-        discover(&opts, &mut self.cases, notifier.as_mut()).unwrap_or_else(|err| {
-            eprintln!("{}", err);
-            std::process::exit(1)
+        if let Some(name) = std::env::var_os(subprocess::CASE_ENV) {
+            let state = new_state(&opts).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1)
+            });
+            install_panic_hook();
+            let sources = std::mem::take(&mut self.sources);
+            let cases = self.cases.into_iter().chain(sources.into_iter().flatten());
+            let cases = repeat::repeat(cases.collect(), opts.repeat);
+            subprocess::child_main(&name, &cases, &state);
+        }
+
+        let suite = self.suite_name.clone().unwrap_or_else(|| {
+            self.raw
+                .first()
+                .and_then(|bin| std::path::Path::new(bin).file_stem())
+                .map(|bin| bin.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "test".to_owned())
         });
+        let mut output;
+        let mut capture;
+        let notifier: &mut dyn notify::Notifier = match events {
+            Some(events) => {
+                capture = notify::EventsNotifier::new(events);
+                &mut capture
+            }
+            None => {
+                output = notifier(&opts, &suite)?;
+                output.as_mut()
+            }
+        };
+        let mut notifier = ConcludingNotifier {
+            notifier,
+            conclusion: Conclusion::default(),
+        };
+        discover(
+            &opts,
+            &mut self.cases,
+            std::mem::take(&mut self.sources),
+            &self.filters,
+            self.shuffle_seed,
+            self.shuffle_with.as_ref(),
+            &mut notifier,
+        )?;
 
-        if !opts.list {
-            match run(&opts, self.cases, notifier.as_mut()) {
-                Ok(true) => {}
-                Ok(false) => std::process::exit(ERROR_EXIT_CODE),
-                Err(e) => {
-                    eprintln!("error: io error when listing tests: {e:?}");
-                    std::process::exit(ERROR_EXIT_CODE)
-                }
+        if !opts.list && !opts.dry_run {
+            if let Some(flag) = unsupported_flag(&opts) {
+                return Err(format!("error: `{flag}` is not yet supported").into());
+            }
+            if opts.nocapture && !streams_output(&opts) {
+                return Err(
+                    "error: `--nocapture` is only supported with `--format=json` and `--run-in-subprocess`"
+                        .into(),
+                );
+            }
+            if opts.graceful_interrupt {
+                interrupt::install()
+                    .map_err(|err| format!("error: could not catch interrupts: {err}"))?;
             }
+            let suite = self.suite_name.as_deref();
+            run(&opts, suite, self.cases, &mut notifier).map_err(RunIoError)?;
+            notifier.conclusion.ran = true;
+            notifier.conclusion.interrupted = interrupt::is_interrupted();
         }
 
-        std::process::exit(0)
+        Ok((notifier.conclusion, opts))
     }
 }
 
 const ERROR_EXIT_CODE: i32 = 101;
 
-fn parse(parser: &mut cli::Parser) -> cli::Result<libtest_lexarg::TestOpts> {
-    let mut test_opts = libtest_lexarg::TestOptsParseState::new();
+/// Writing the results failed while running the cases
+#[derive(Debug)]
+struct RunIoError(std::io::Error);
+
+impl std::fmt::Display for RunIoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: io error: {}", self.0)
+    }
+}
+
+impl std::error::Error for RunIoError {}
 
+fn parse(
+    parser: &mut cli::Parser,
+    mut test_opts: libtest_lexarg::TestOptsParseState,
+) -> cli::Result<libtest_lexarg::TestOpts> {
     let bin = parser.bin();
     while let Some(arg) = parser.next() {
         match arg {
@@ -101,9 +480,12 @@ fn parse(parser: &mut cli::Parser) -> cli::Result<libtest_lexarg::TestOpts> {
                 cli::Arg::Short(v) => {
                     format!("unrecognized `-{v}` flag")
                 }
-                cli::Arg::Long(v) => {
-                    format!("unrecognized `--{v}` flag")
-                }
+                cli::Arg::Long(v) => match suggest_flag(v) {
+                    Some(flag) => {
+                        format!("unrecognized `--{v}` flag, did you mean `--{flag}`?")
+                    }
+                    None => format!("unrecognized `--{v}` flag"),
+                },
                 cli::Arg::Escape => "handled `--`".to_owned(),
                 cli::Arg::Value(v) => {
                     format!("unrecognized `{}` value", v.to_string_lossy())
@@ -116,62 +498,403 @@ fn parse(parser: &mut cli::Parser) -> cli::Result<libtest_lexarg::TestOpts> {
         }
     }
 
-    let mut opts = test_opts.finish()?;
+    test_opts.finish()
+}
+
+/// Fill in platform-dependent defaults, for both parsed and [`Harness::with_opts`] options
+///
+/// Afterwards, `test_threads` is always set.  `1` means every case runs synchronously on the
+/// calling thread, without spawning any.
+fn resolve(mut opts: libtest_lexarg::TestOpts) -> libtest_lexarg::TestOpts {
+    opts.run_tests |= !opts.bench_benchmarks;
     // If the platform is single-threaded we're just going to run
     // the test synchronously, regardless of the concurrency
     // level.
     let supports_threads = !cfg!(target_os = "emscripten") && !cfg!(target_family = "wasm");
-    opts.test_threads = if cfg!(feature = "threads") && supports_threads {
+    let threads = if cfg!(feature = "threads") && supports_threads {
         opts.test_threads
             .or_else(|| std::thread::available_parallelism().ok())
     } else {
         None
     };
-    Ok(opts)
+    opts.test_threads = threads.or(std::num::NonZeroUsize::new(1));
+    opts
 }
 
-fn notifier(opts: &libtest_lexarg::TestOpts) -> std::io::Result<Box<dyn notify::Notifier>> {
-    let stdout = anstream::stdout();
-    let notifier: Box<dyn notify::Notifier> = match opts.format {
+/// A flag that is accepted but not acted on when running
+fn unsupported_flag(opts: &libtest_lexarg::TestOpts) -> Option<&'static str> {
+    if opts.exclude_should_panic {
+        Some("--exclude-should-panic")
+    } else if opts.time_options.is_some_and(|t| t.error_on_excess) {
+        Some("--ensure-time")
+    } else if opts.options.display_output {
+        Some("--show-output")
+    } else {
+        None
+    }
+}
+
+/// Whether `--nocapture` output can be reported, see [`notify::Event::CaseOutput`]
+///
+/// Cases write straight to the process's stdout, so only output from a child process can be told
+/// apart from the results.
+fn streams_output(opts: &libtest_lexarg::TestOpts) -> bool {
+    let json = matches!(opts.format, OutputFormat::Json | OutputFormat::JsonPretty)
+        || opts.json_stderr_pretty;
+    json && runs_in_subprocess(opts)
+}
+
+/// Whether each case runs in a child process, see [`subprocess`]
+fn runs_in_subprocess(opts: &libtest_lexarg::TestOpts) -> bool {
+    // A panic can only be caught in-process with `panic=unwind`
+    (opts.run_in_subprocess || opts.options.panic_abort) && !opts.force_run_in_process
+}
+
+/// The test names from `--filter-file`
+///
+/// These always match exactly, unlike the filters on the command line.
+fn read_filter_file(
+    opts: &libtest_lexarg::TestOpts,
+) -> std::io::Result<Option<std::collections::HashSet<String>>> {
+    let Some(path) = opts.filter_file.as_deref() else {
+        return Ok(None);
+    };
+    let error = |e: &dyn std::fmt::Display| {
+        std::io::Error::other(format!(
+            "error: could not read filter file `{}`: {e}",
+            path.display()
+        ))
+    };
+    let names = if path == std::path::Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).map_err(|e| error(&e))?
+    } else {
+        std::fs::read_to_string(path).map_err(|e| error(&e))?
+    };
+    let names = names
+        .lines()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_owned())
+        .collect::<std::collections::HashSet<_>>();
+    // No filters would mean running everything, which is never what an empty selection meant
+    if names.is_empty() {
+        return Err(error(&"no test names"));
+    }
+    Ok(Some(names))
+}
+
+/// The known long flag closest to `unknown`, if any is close enough to be a typo
+fn suggest_flag(unknown: &str) -> Option<&'static str> {
+    libtest_lexarg::LONG_FLAGS
+        .iter()
+        .copied()
+        .chain(["help"])
+        .map(|flag| (levenshtein(unknown, flag), flag))
+        .filter(|(distance, flag)| *distance <= 2 && *distance < flag.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, flag)| flag)
+}
+
+/// Split `args` on whitespace, like a shell without expansions
+///
+/// Quotes and `\` keep whitespace in an argument.  Within `"`, `\` only escapes `"` and `\`.
+fn split_args(args: &str) -> Result<Vec<String>, &'static str> {
+    let mut split = Vec::new();
+    let mut current = None::<String>;
+    let mut chars = args.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                split.extend(current.take());
+            }
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err("unterminated `'`"),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err("unterminated `\"`"),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err("unterminated `\"`"),
+                    }
+                }
+            }
+            '\\' => {
+                let c = chars.next().ok_or("trailing `\\`")?;
+                current.get_or_insert_with(String::new).push(c);
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    split.extend(current);
+    Ok(split)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(a != *b);
+            current[j + 1] = substitute.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
+fn notifier(
+    opts: &libtest_lexarg::TestOpts,
+    suite: &str,
+) -> std::io::Result<Box<dyn notify::Notifier>> {
+    use std::io::IsTerminal as _;
+
+    #[cfg(feature = "json")]
+    let baseline = opts
+        .baseline
+        .as_deref()
+        .map(notify::load_baseline)
+        .transpose()?;
+    #[cfg(not(feature = "json"))]
+    let baseline = match opts.baseline {
+        Some(_) => return Err(std::io::Error::other("`--baseline` is not supported")),
+        None => None,
+    };
+    let baseline = baseline.as_ref();
+
+    let mut notifiers = if opts.json_stderr_pretty {
+        let json = match opts.format {
+            OutputFormat::JsonPretty => OutputFormat::JsonPretty,
+            _ => OutputFormat::Json,
+        };
+        let json = format_notifier(opts, json, anstream::stdout(), false, suite, baseline)?;
+        let format = match opts.format {
+            OutputFormat::Terse => OutputFormat::Terse,
+            _ => OutputFormat::Pretty,
+        };
+        let terminal = std::io::stderr().is_terminal();
+        let human = format_notifier(opts, format, anstream::stderr(), terminal, suite, baseline)?;
+        vec![json, human]
+    } else {
+        vec![format_notifier(
+            opts,
+            opts.format,
+            anstream::stdout(),
+            std::io::stdout().is_terminal(),
+            suite,
+            baseline,
+        )?]
+    };
+
+    if let Some(path) = &opts.logfile {
+        let file = std::fs::File::create(path).map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!("could not create logfile `{}`: {e}", path.display()),
+            )
+        })?;
+        // A file is never a terminal, so colors are only kept for `--color=always`
+        let file = anstream::AutoStream::new(file, anstream::ColorChoice::global());
+        notifiers.push(format_notifier(
+            opts,
+            opts.format,
+            file,
+            false,
+            suite,
+            baseline,
+        )?);
+    }
+
+    if let Some(path) = &opts.junit_path {
+        if !opts.list && !opts.dry_run {
+            let file = std::fs::File::create(path).map_err(|e| {
+                std::io::Error::new(
+                    e.kind(),
+                    format!("could not create JUnit report `{}`: {e}", path.display()),
+                )
+            })?;
+            notifiers.push(format_notifier(
+                opts,
+                OutputFormat::Junit,
+                file,
+                false,
+                suite,
+                baseline,
+            )?);
+        }
+    }
+
+    // Only when the results are redirected, so the status line doesn't garble them
+    if opts.progress && !opts.list && !opts.dry_run && !opts.json_stderr_pretty {
+        let stderr = std::io::stderr();
+        if stderr.is_terminal() && !std::io::stdout().is_terminal() {
+            notifiers.push(Box::new(notify::ProgressNotifier::new(stderr)));
+        }
+    }
+
+    #[cfg(feature = "json")]
+    if let Some(path) = &opts.save_results {
+        notifiers.push(Box::new(notify::ResultsNotifier::new(path.clone())));
+    }
+    #[cfg(not(feature = "json"))]
+    if opts.save_results.is_some() {
+        return Err(std::io::Error::other("`--save-results` is not supported"));
+    }
+
+    if notifiers.len() == 1 {
+        Ok(notifiers.remove(0))
+    } else {
+        Ok(Box::new(notify::TeeNotifier::new(notifiers)))
+    }
+}
+
+fn format_notifier<W: std::io::Write + 'static>(
+    opts: &libtest_lexarg::TestOpts,
+    format: OutputFormat,
+    stdout: W,
+    terminal: bool,
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))] suite: &str,
+    baseline: Option<&std::collections::BTreeSet<String>>,
+) -> std::io::Result<Box<dyn notify::Notifier>> {
+    if opts.list {
+        return list_notifier(opts, format, stdout, suite);
+    }
+    let notifier: Box<dyn notify::Notifier> = match format {
         #[cfg(feature = "json")]
-        OutputFormat::Json => Box::new(notify::JsonNotifier::new(stdout)),
+        OutputFormat::Json | OutputFormat::JsonPretty => Box::new(
+            notify::JsonNotifier::new(stdout, suite.to_owned())
+                .pretty(format == OutputFormat::JsonPretty)
+                // Dry runs are all `discover-case` events
+                .quiet(opts.quiet && !opts.dry_run),
+        ),
         #[cfg(not(feature = "json"))]
-        OutputFormat::Json => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "`--format=json` is not supported",
-            ));
+        OutputFormat::Json | OutputFormat::JsonPretty => {
+            return Err(std::io::Error::other("`--format=json` is not supported"));
         }
-        _ if opts.list => Box::new(notify::TerseListNotifier::new(stdout)),
-        OutputFormat::Pretty => Box::new(notify::PrettyRunNotifier::new(stdout)),
-        OutputFormat::Terse => Box::new(notify::TerseRunNotifier::new(stdout)),
+        _ if opts.dry_run => Box::new(notify::DryRunNotifier::new(
+            stdout,
+            opts.run_ignored != libtest_lexarg::RunIgnored::No,
+        )),
+        OutputFormat::Pretty => Box::new(
+            notify::PrettyRunNotifier::new(stdout)
+                .report_time(opts.time_options.is_some())
+                .result_line(result_line())
+                .subtotals(opts.subtotals)
+                .baseline(baseline.cloned()),
+        ),
+        OutputFormat::Terse => Box::new(
+            notify::TerseRunNotifier::new(stdout)
+                .count(terminal)
+                .width(
+                    terminal
+                        .then(terminal_size::terminal_size)
+                        .flatten()
+                        .map(|(width, _)| usize::from(width.0)),
+                )
+                .result_line(result_line())
+                .subtotals(opts.subtotals)
+                .baseline(baseline.cloned()),
+        ),
         #[cfg(feature = "junit")]
-        OutputFormat::Junit => Box::new(notify::JunitRunNotifier::new(stdout)),
+        OutputFormat::Junit => Box::new(
+            notify::JunitRunNotifier::new(stdout).root_classname(opts.junit_classname.clone()),
+        ),
         #[cfg(not(feature = "junit"))]
         OutputFormat::Junit => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "`--format=junit` is not supported",
+            return Err(std::io::Error::other("`--format=junit` is not supported"));
+        }
+    };
+    Ok(notifier)
+}
+
+/// Notifier for `--list`, from `--list-format` or else following `format`
+fn list_notifier<W: std::io::Write + 'static>(
+    opts: &libtest_lexarg::TestOpts,
+    format: OutputFormat,
+    stdout: W,
+    #[cfg_attr(not(feature = "json"), allow(unused_variables))] suite: &str,
+) -> std::io::Result<Box<dyn notify::Notifier>> {
+    let list_format = opts.list_format.unwrap_or(match format {
+        OutputFormat::Json | OutputFormat::JsonPretty => libtest_lexarg::ListFormat::Json,
+        _ => libtest_lexarg::ListFormat::Terse,
+    });
+    let notifier: Box<dyn notify::Notifier> = match list_format {
+        #[cfg(feature = "json")]
+        // Listing is all `discover-case` events, so `--quiet` doesn't apply
+        libtest_lexarg::ListFormat::Json => Box::new(
+            notify::JsonNotifier::new(stdout, suite.to_owned())
+                .pretty(format == OutputFormat::JsonPretty && opts.list_format.is_none()),
+        ),
+        #[cfg(not(feature = "json"))]
+        libtest_lexarg::ListFormat::Json => {
+            return Err(std::io::Error::other(
+                "`--list-format=json` is not supported",
             ));
         }
+        libtest_lexarg::ListFormat::Plain => {
+            Box::new(notify::TerseListNotifier::new(stdout).plain(true))
+        }
+        libtest_lexarg::ListFormat::Terse if opts.list_tree => {
+            Box::new(notify::PrettyListNotifier::new(stdout))
+        }
+        libtest_lexarg::ListFormat::Terse => Box::new(notify::TerseListNotifier::new(stdout)),
     };
     Ok(notifier)
 }
 
+/// Resolve `--color=auto` from `CARGO_TERM_COLOR`, `NO_COLOR`, and `CLICOLOR_FORCE`
+///
+/// See <https://no-color.org> and <https://bixense.com/clicolors/>.  `NO_COLOR` wins when both
+/// are set.  `CARGO_TERM_COLOR=always|never` takes precedence over both, to follow `cargo`.
+fn auto_color() -> anstream::ColorChoice {
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+    let cargo = std::env::var("CARGO_TERM_COLOR").ok();
+    match cargo.as_deref() {
+        Some("always") => anstream::ColorChoice::Always,
+        Some("never") => anstream::ColorChoice::Never,
+        _ if var("NO_COLOR").is_some() => anstream::ColorChoice::Never,
+        _ if var("CLICOLOR_FORCE").is_some_and(|v| v != "0") => anstream::ColorChoice::Always,
+        _ => anstream::ColorChoice::Auto,
+    }
+}
+
+/// Whether `LIBTEST2_RESULT_LINE` asks for a machine-readable final line
+fn result_line() -> bool {
+    std::env::var_os("LIBTEST2_RESULT_LINE").is_some_and(|v| !v.is_empty() && v != "0")
+}
+
 fn discover(
     opts: &libtest_lexarg::TestOpts,
     cases: &mut Vec<Box<dyn Case>>,
+    sources: Vec<CaseSource>,
+    filters: &[Filter],
+    shuffle_seed: Option<u64>,
+    shuffle_with: Option<&Shuffle>,
     notifier: &mut dyn notify::Notifier,
 ) -> std::io::Result<()> {
-    notifier.notify(notify::Event::DiscoverStart)?;
+    // Only the parent discovers cases, so a child doesn't wait on the parent's stdin
+    let named = read_filter_file(opts)?;
+    let named = named.as_ref();
+    notifier.notify(&notify::Event::DiscoverStart)?;
     let timer = std::time::Instant::now();
-
-    // Do this first so it applies to both discover and running
-    cases.sort_unstable_by_key(|case| case.name().to_owned());
-    let seed = shuffle::get_shuffle_seed(opts);
-    if let Some(seed) = seed {
-        shuffle::shuffle_tests(seed, cases);
-    }
+    let mode = run_mode(opts)?;
 
     let matches_filter = |case: &dyn Case, filter: &str| {
         let test_name = case.name();
@@ -181,75 +904,191 @@ fn discover(
             false => test_name.contains(filter),
         }
     };
-    let mut retain_cases = Vec::with_capacity(cases.len());
-    for case in cases.iter() {
-        let filtered_in = opts.filters.is_empty()
-            || opts
-                .filters
+    // With `--exact`, look names up rather than scanning every filter for every case
+    let exact_filters = opts.filter_exact.then(|| {
+        opts.filters
+            .iter()
+            .map(String::as_str)
+            .collect::<std::collections::HashSet<_>>()
+    });
+    let exact_skip = opts.filter_exact.then(|| {
+        opts.skip
+            .iter()
+            .map(String::as_str)
+            .collect::<std::collections::HashSet<_>>()
+    });
+    let matches_any =
+        |case: &dyn Case, filters: &[String], exact: &Option<std::collections::HashSet<&str>>| {
+            match exact {
+                Some(exact) => exact.contains(case.name()),
+                None => filters.iter().any(|filter| matches_filter(case, filter)),
+            }
+        };
+    // Being named in `--filter-file` counts as one more filter
+    let is_named = |case: &dyn Case| named.map(|named| named.contains(case.name()));
+    let is_selected = |case: &dyn Case| {
+        let filtered_in = if opts.filters.is_empty() {
+            is_named(case).unwrap_or(true)
+        } else if opts.filter_all {
+            opts.filters
                 .iter()
-                .any(|filter| matches_filter(case.as_ref(), filter));
-        let filtered_out =
-            !opts.skip.is_empty() && opts.skip.iter().any(|sf| matches_filter(case.as_ref(), sf));
-        let retain_case = filtered_in && !filtered_out;
-        retain_cases.push(retain_case);
-        notifier.notify(notify::Event::DiscoverCase {
-            name: case.name().to_owned(),
-            mode: notify::RunMode::Test,
-            run: retain_case,
-        })?;
-    }
-    let mut retain_cases = retain_cases.into_iter();
-    cases.retain(|_| retain_cases.next().unwrap());
-
-    notifier.notify(notify::Event::DiscoverComplete {
-        elapsed_s: notify::Elapsed(timer.elapsed()),
-        seed,
-    })?;
-
-    Ok(())
-}
+                .all(|filter| matches_filter(case, filter))
+                && is_named(case).unwrap_or(true)
+        } else {
+            matches_any(case, &opts.filters, &exact_filters) || is_named(case).unwrap_or(false)
+        };
+        let filtered_out = !opts.skip.is_empty() && matches_any(case, &opts.skip, &exact_skip);
+        let tagged_in = opts.include_tags.is_empty()
+            || case
+                .tags()
+                .iter()
+                .any(|tag| opts.include_tags.iter().any(|t| t == tag));
+        let tagged_out = case
+            .tags()
+            .iter()
+            .any(|tag| opts.exclude_tags.iter().any(|t| t == tag));
+        // Benchmarks are run as tests but `--bench` only runs benchmarks
+        let mode_matches = mode == notify::RunMode::Test || case.mode() == mode;
+        let ignored_matches = match opts.run_ignored {
+            libtest_lexarg::RunIgnored::Yes | libtest_lexarg::RunIgnored::No => true,
+            libtest_lexarg::RunIgnored::Only => case.is_ignored().is_some(),
+        };
+        mode_matches
+            && ignored_matches
+            && filtered_in
+            && !filtered_out
+            && tagged_in
+            && !tagged_out
+            && filters.iter().all(|filter| filter(case))
+    };
+    let discover_case = |case: &dyn Case, run: bool| notify::Event::DiscoverCase {
+        name: case.name().to_owned(),
+        mode: case.mode(),
+        run,
+        ignored: case.is_ignored().is_some(),
+        description: case.describe().map(|d| d.to_owned()),
+    };
 
-fn run(
-    opts: &libtest_lexarg::TestOpts,
-    cases: Vec<Box<dyn Case>>,
-    notifier: &mut dyn notify::Notifier,
-) -> std::io::Result<bool> {
-    notifier.notify(notify::Event::SuiteStart)?;
-    let timer = std::time::Instant::now();
+    let seed = shuffle::get_shuffle_seed(opts, shuffle_seed);
+    // When sorted by name, cases that were already held on to are listed among the rest,
+    // otherwise the ones filtered out are listed first
+    let by_name = matches!(opts.order, libtest_lexarg::TestOrder::Name) && seed.is_none();
 
-    if opts.force_run_in_process {
-        todo!("`--force-run-in-process` is not yet supported");
+    // Only hold on to the cases that will be run, checking each case once
+    let mut num_filtered_out = 0;
+    let mut filtered_out = Vec::new();
+    for case in std::mem::take(cases) {
+        if is_selected(case.as_ref()) {
+            cases.push(case);
+        } else if by_name {
+            filtered_out.push(case);
+        } else {
+            num_filtered_out += 1;
+            notifier.notify(&discover_case(case.as_ref(), false))?;
+        }
     }
-    if opts.exclude_should_panic {
-        todo!("`--exclude-should-panic` is not yet supported");
+    for case in sources.into_iter().flatten() {
+        if is_selected(case.as_ref()) {
+            cases.push(case);
+        } else {
+            num_filtered_out += 1;
+            notifier.notify(&discover_case(case.as_ref(), false))?;
+        }
     }
-    if opts.nocapture {
-        todo!("`--nocapture` is not yet supported");
+
+    // Results are reported by name, so one would be lost or mistaken for the other
+    let mut names = std::collections::HashSet::with_capacity(cases.len());
+    if let Some(case) = cases.iter().find(|case| !names.insert(case.name())) {
+        return Err(std::io::Error::other(format!(
+            "error: duplicate test name `{}`",
+            case.name()
+        )));
     }
-    if opts.time_options.is_some() {
-        todo!("`--report-time` / `--ensure-time` are not yet supported");
+
+    // Do this first so it applies to both discover and running
+    match opts.order {
+        libtest_lexarg::TestOrder::Name => {
+            cases.sort_unstable_by(|a, b| a.name().cmp(b.name()));
+            filtered_out.sort_unstable_by(|a, b| a.name().cmp(b.name()));
+        }
+        libtest_lexarg::TestOrder::Declared => {}
     }
-    if opts.options.display_output {
-        todo!("`--show-output` is not yet supported");
+    if let Some(seed) = seed {
+        match shuffle_with {
+            Some(shuffle_with) => shuffle_with(seed, cases),
+            None => shuffle::shuffle_tests(seed, cases),
+        }
     }
-    if opts.options.panic_abort {
-        todo!("panic-abort is not yet supported");
+
+    let partition = opts.partition.map(|partition| {
+        let names = cases.iter().map(|case| case.name().to_owned()).collect();
+        partition::select(partition, names)
+    });
+    let in_partition = |case: &dyn Case| {
+        partition
+            .as_ref()
+            .map(|partition| partition.contains(case.name()))
+            .unwrap_or(true)
+    };
+
+    let mut filtered_out = filtered_out.into_iter().peekable();
+    let mut selected = Vec::with_capacity(cases.len());
+    for case in std::mem::take(cases) {
+        while let Some(filtered) = filtered_out.next_if(|f| f.name() < case.name()) {
+            num_filtered_out += 1;
+            notifier.notify(&discover_case(filtered.as_ref(), false))?;
+        }
+        if !in_partition(case.as_ref()) {
+            num_filtered_out += 1;
+            notifier.notify(&discover_case(case.as_ref(), false))?;
+            continue;
+        }
+        // Each run of a case is discovered on its own, but a listing shows the case once
+        let runs = if opts.list {
+            vec![case]
+        } else {
+            repeat::repeat(vec![case], opts.repeat)
+        };
+        for case in runs {
+            notifier.notify(&discover_case(case.as_ref(), true))?;
+            selected.push(case);
+        }
     }
-    if opts.logfile.is_some() {
-        todo!("`--logfile` is not yet supported");
+    for filtered in filtered_out {
+        num_filtered_out += 1;
+        notifier.notify(&discover_case(filtered.as_ref(), false))?;
     }
+    *cases = selected;
 
-    let threads = opts.test_threads.map(|t| t.get()).unwrap_or(1);
+    notifier.notify(&notify::Event::DiscoverComplete {
+        elapsed_s: notify::Elapsed(timer.elapsed()),
+        seed,
+        num_to_run: cases.len(),
+        num_filtered_out,
+    })?;
+
+    Ok(())
+}
 
+fn new_state(opts: &libtest_lexarg::TestOpts) -> std::io::Result<State> {
     let mut state = State::new();
     let run_ignored = match opts.run_ignored {
         libtest_lexarg::RunIgnored::Yes | libtest_lexarg::RunIgnored::Only => true,
         libtest_lexarg::RunIgnored::No => false,
     };
+    state.set_mode(run_mode(opts)?);
+    state.set_run_ignored(run_ignored);
+    state.set_nocapture(opts.nocapture);
+    state.set_test_threads(opts.test_threads.map(|t| t.get()).unwrap_or(1));
+    state.set_timeout(opts.timeout);
+    state.set_iterations(opts.iterations.map(|i| i.get()).unwrap_or(1));
+    Ok(state)
+}
+
+fn run_mode(opts: &libtest_lexarg::TestOpts) -> std::io::Result<notify::RunMode> {
     let mode = match (opts.run_tests, opts.bench_benchmarks) {
         (true, true) => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(std::io::Error::other(
                 "`--test` and `-bench` are mutually exclusive",
             ));
         }
@@ -257,12 +1096,31 @@ fn run(
         (false, true) => notify::RunMode::Bench,
         (false, false) => unreachable!("libtest-lexarg` should always ensure at least one is set"),
     };
-    state.set_mode(mode);
-    state.set_run_ignored(run_ignored);
-    let state = std::sync::Arc::new(state);
+    Ok(mode)
+}
+
+fn run(
+    opts: &libtest_lexarg::TestOpts,
+    suite: Option<&str>,
+    cases: Vec<Box<dyn Case>>,
+    notifier: &mut dyn notify::Notifier,
+) -> std::io::Result<bool> {
+    notifier.notify(&notify::Event::SuiteStart {
+        suite: suite.map(|s| s.to_owned()),
+    })?;
+    let timer = std::time::Instant::now();
+    let timeline = opts.timeline.then_some(timer);
+
+    install_panic_hook();
+
+    let threads = opts.test_threads.map(|t| t.get()).unwrap_or(1);
+    let isolate = runs_in_subprocess(opts).then(|| subprocess::child_args(opts));
+
+    let state = std::sync::Arc::new(new_state(opts)?);
 
     let mut success = true;
 
+    // Only `concurrent_cases` spawn workers, so a single thread never spawns any
     let (exclusive_cases, concurrent_cases) = if threads == 1 || cases.len() == 1 {
         (cases, vec![])
     } else {
@@ -272,126 +1130,290 @@ fn run(
     };
     if !concurrent_cases.is_empty() {
         notifier.threaded(true);
-        struct RunningTest {
-            join_handle: std::thread::JoinHandle<()>,
-        }
-
-        impl RunningTest {
-            fn join(self, event: &mut notify::Event) {
-                if self.join_handle.join().is_err() {
-                    if let notify::Event::CaseComplete {
-                        status, message, ..
-                    } = event
-                    {
-                        if status.is_none() {
-                            *status = Some(notify::RunStatus::Failed);
-                            *message = Some("panicked after reporting success".to_owned());
-                        }
-                    }
-                }
-            }
-        }
-
-        // Use a deterministic hasher
-        type TestMap = std::collections::HashMap<
-            String,
-            RunningTest,
-            std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>,
-        >;
-
-        let sync_success = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(success));
-        let mut running_tests: TestMap = Default::default();
-        let mut pending = 0;
+        let (main_thread_cases, concurrent_cases) = concurrent_cases
+            .into_iter()
+            .partition::<Vec<_>, _>(|c| c.main_thread());
+        let workers_needed = threads.min(concurrent_cases.len());
+        let queue = std::sync::Arc::new(std::sync::Mutex::new(std::collections::VecDeque::from(
+            concurrent_cases,
+        )));
         let (tx, rx) = std::sync::mpsc::channel::<notify::Event>();
-        let mut remaining = std::collections::VecDeque::from(concurrent_cases);
-        while pending > 0 || !remaining.is_empty() {
-            while pending < threads && !remaining.is_empty() {
-                let case = remaining.pop_front().unwrap();
-                let name = case.name().to_owned();
-
-                let cfg = std::thread::Builder::new().name(name.to_owned());
-                let tx = tx.clone();
-                let case = std::sync::Arc::new(case);
-                let case_fallback = case.clone();
-                let state = state.clone();
-                let state_fallback = state.clone();
-                let sync_success = sync_success.clone();
-                let sync_success_fallback = sync_success.clone();
-                let join_handle = cfg.spawn(move || {
-                    let mut notifier = SenderNotifier { tx: tx.clone() };
-                    let case_success = run_case(case.as_ref().as_ref(), &state, &mut notifier)
-                        .expect("`SenderNotifier` is infallible");
-                    if !case_success {
-                        sync_success.store(case_success, std::sync::atomic::Ordering::Relaxed);
-                    }
-                });
-                match join_handle {
-                    Ok(join_handle) => {
-                        running_tests.insert(name.clone(), RunningTest { join_handle });
-                        pending += 1;
-                    }
-                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                        // `ErrorKind::WouldBlock` means hitting the thread limit on some
-                        // platforms, so run the test synchronously here instead.
-                        let case_success =
-                            run_case(case_fallback.as_ref().as_ref(), &state_fallback, notifier)
-                                .expect("`SenderNotifier` is infallible");
-                        if !case_success {
-                            sync_success_fallback
-                                .store(case_success, std::sync::atomic::Ordering::Relaxed);
-                        }
-                    }
-                    Err(e) => {
-                        return Err(e);
-                    }
+        let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut workers = Vec::with_capacity(workers_needed);
+        for _ in 0..workers_needed {
+            let cfg = std::thread::Builder::new();
+            let queue = queue.clone();
+            let state = state.clone();
+            let tx = tx.clone();
+            let cancel = cancel.clone();
+            let isolate = isolate.clone();
+            let fail_fast = opts.fail_fast;
+            let worker = move || {
+                run_worker(
+                    &queue,
+                    &state,
+                    isolate.as_deref(),
+                    timeline,
+                    tx,
+                    &cancel,
+                    fail_fast,
+                );
+            };
+            match cfg.spawn(worker) {
+                Ok(join_handle) => {
+                    workers.push(join_handle);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock && !workers.is_empty() => {
+                    // `ErrorKind::WouldBlock` means hitting the thread limit on some
+                    // platforms, so make do with the workers we have.
+                    break;
+                }
+                Err(e) => {
+                    return Err(e);
                 }
             }
+        }
+        // Only the workers should keep the channel open
+        drop(tx);
 
-            let mut event = rx.recv().unwrap();
-            if let notify::Event::CaseComplete { name, .. } = &event {
-                let running_test = running_tests.remove(name).unwrap();
-                running_test.join(&mut event);
-                pending -= 1;
+        for case in main_thread_cases {
+            if cancel.load(std::sync::atomic::Ordering::SeqCst) || interrupt::is_interrupted() {
+                break;
             }
-            notifier.notify(event)?;
-            success &= sync_success.load(std::sync::atomic::Ordering::SeqCst);
+            success &= run_case(
+                case.as_ref(),
+                &state,
+                isolate.as_deref(),
+                timeline,
+                notifier,
+            )?;
             if !success && opts.fail_fast {
-                break;
+                cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+
+        for event in rx.iter() {
+            if let notify::Event::CaseComplete {
+                status: Some(status),
+                ..
+            } = &event
+            {
+                success &= !status.is_failure();
             }
+            notifier.notify(&event)?;
+        }
+        for worker in workers {
+            // Panics are caught and reported per-case
+            let _ = worker.join();
         }
     }
 
     if !exclusive_cases.is_empty() {
         notifier.threaded(false);
         for case in exclusive_cases {
-            success &= run_case(case.as_ref(), &state, notifier)?;
-            if !success && opts.fail_fast {
+            if (!success && opts.fail_fast) || interrupt::is_interrupted() {
                 break;
             }
+            success &= run_case(
+                case.as_ref(),
+                &state,
+                isolate.as_deref(),
+                timeline,
+                notifier,
+            )?;
         }
     }
 
-    notifier.notify(notify::Event::SuiteComplete {
+    notifier.notify(&notify::Event::SuiteComplete {
         elapsed_s: notify::Elapsed(timer.elapsed()),
+        aborted: opts.fail_fast && !success,
     })?;
 
     Ok(success)
 }
 
+/// Run cases from `queue` until it is empty or the run is cancelled
+///
+/// The worker owns each case while running it and sends its result with the events, so nothing
+/// in flight is looked up by name.
+fn run_worker(
+    queue: &std::sync::Mutex<std::collections::VecDeque<Box<dyn Case>>>,
+    state: &State,
+    isolate: Option<&[std::ffi::OsString]>,
+    timeline: Option<std::time::Instant>,
+    tx: std::sync::mpsc::Sender<notify::Event>,
+    cancel: &std::sync::atomic::AtomicBool,
+    fail_fast: bool,
+) {
+    loop {
+        if cancel.load(std::sync::atomic::Ordering::SeqCst) || interrupt::is_interrupted() {
+            break;
+        }
+        let case = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+        let Some(case) = case else {
+            break;
+        };
+
+        let mut notifier = SenderNotifier {
+            tx: tx.clone(),
+            complete: None,
+        };
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            run_case(case.as_ref(), state, isolate, timeline, &mut notifier)
+                .expect("`SenderNotifier` is infallible")
+        }))
+        .is_err();
+        let complete = notifier.complete.take();
+        // Without this, a case that panicked before reporting anything would be lost
+        let complete = complete.or_else(|| {
+            panicked.then(|| notify::Event::CaseComplete {
+                name: case.name().to_owned(),
+                mode: case.mode(),
+                status: Some(notify::RunStatus::Errored),
+                message: Some("panicked before reporting a result".to_owned()),
+                elapsed_s: None,
+                timings: None,
+                metadata: Default::default(),
+                logs: None,
+                diff: None,
+                location: None,
+                failure_location: None,
+                #[cfg(feature = "json")]
+                at_s: None,
+            })
+        });
+        if let Some(mut event) = complete {
+            if let notify::Event::CaseComplete {
+                status, message, ..
+            } = &mut event
+            {
+                if panicked && status.is_none() {
+                    *status = Some(notify::RunStatus::Failed);
+                    *message = Some("panicked after reporting success".to_owned());
+                }
+                if fail_fast && status.is_some_and(|s| s.is_failure()) {
+                    cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+            }
+            // If the receiver doesn't care, neither do we
+            let _ = tx.send(event);
+        }
+    }
+}
+
+/// Run `case`, in a child process passed the arguments from `isolate`
+///
+/// Events are timestamped relative to the start of the run with `timeline`.
 fn run_case(
     case: &dyn Case,
     state: &State,
+    isolate: Option<&[std::ffi::OsString]>,
+    timeline: Option<std::time::Instant>,
     notifier: &mut dyn notify::Notifier,
 ) -> std::io::Result<bool> {
-    notifier.notify(notify::Event::CaseStart {
+    // Timestamps are only reported in JSON
+    #[cfg(feature = "json")]
+    let at_s = || timeline.map(|start| notify::Elapsed(start.elapsed()));
+    #[cfg(not(feature = "json"))]
+    let _ = timeline;
+    notifier.notify(&notify::Event::CaseStart {
         name: case.name().to_owned(),
+        #[cfg(feature = "json")]
+        at_s: at_s(),
     })?;
     let timer = std::time::Instant::now();
 
-    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        __rust_begin_short_backtrace(|| case.run(state))
-    }))
-    .unwrap_or_else(|e| {
+    // The case fails if any iteration does, so stop at the first one that doesn't pass
+    let mut durations = Vec::with_capacity(state.iterations());
+    // From streaming the case's output, reported once the case is done
+    let mut written = Ok(());
+    let (outcome, state) = loop {
+        let case_state = state.for_case(case);
+        let iteration = std::time::Instant::now();
+        let outcome = match isolate {
+            Some(args) if case_state.is_nocapture() => {
+                let mut on_line = |stream, line| {
+                    if written.is_ok() {
+                        written = notifier.notify(&notify::Event::CaseOutput {
+                            name: case.name().to_owned(),
+                            stream,
+                            line,
+                        });
+                    }
+                };
+                subprocess::run_case(case, &case_state, args, Some(&mut on_line))
+            }
+            Some(args) => subprocess::run_case(case, &case_state, args, None),
+            None => run_case_outcome(case, &case_state),
+        };
+        durations.push(iteration.elapsed());
+        if outcome.is_err() || durations.len() == state.iterations() {
+            break (outcome, case_state);
+        }
+    };
+    written?;
+
+    let err = outcome.as_ref().err();
+    let status = err.map(|e| e.status());
+    let message = err.and_then(|e| e.message());
+    let diff = err
+        .and_then(|e| e.cause())
+        .and_then(|c| c.downcast_ref::<crate::diff::Diff>())
+        .cloned();
+    let samples = state.take_bench_samples();
+    if status.is_none() && !samples.is_empty() {
+        let mut sorted = samples.clone();
+        sorted.sort_unstable();
+        notifier.notify(&notify::Event::BenchComplete {
+            name: case.name().to_owned(),
+            ns_per_iter: sorted[sorted.len() / 2],
+            samples,
+        })?;
+    }
+    notifier.notify(&notify::Event::CaseComplete {
+        name: case.name().to_owned(),
+        mode: case.mode(),
+        status,
+        message,
+        elapsed_s: Some(notify::Elapsed(timer.elapsed())),
+        timings: notify::Timings::new(durations),
+        metadata: state.take_metadata(),
+        logs: Some(state.take_logs())
+            .filter(|logs| !logs.is_empty() && status.is_some_and(|s| s.is_failure())),
+        diff,
+        location: case.source().map(|s| s.to_string()),
+        failure_location: err.and_then(|e| e.location()).map(|l| l.to_owned()),
+        #[cfg(feature = "json")]
+        at_s: at_s(),
+    })?;
+
+    Ok(!status.is_some_and(|s| s.is_failure()))
+}
+
+#[cfg(feature = "tracing")]
+use capture::capture;
+
+/// Without the `tracing` feature, there is nothing to capture
+#[cfg(not(feature = "tracing"))]
+fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    (f(), String::new())
+}
+
+/// Run `case` in this process, catching any panic
+pub(crate) fn run_case_outcome(case: &dyn Case, state: &State) -> RunResult {
+    PANIC_LOCATION.with(|l| l.take());
+    let (outcome, logs) = capture(|| {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            match case.is_ignored() {
+                Some("") => state.ignore()?,
+                Some(reason) => state.ignore_for(reason)?,
+                None => {}
+            }
+            __rust_begin_short_backtrace(|| case.run(state))
+        }))
+    });
+    state.set_logs(logs);
+    outcome.unwrap_or_else(|e| {
         // The `panic` information is just an `Any` object representing the
         // value the panic was invoked with. For most panics (which use
         // `panic!` like `println!`), this is either `&str` or `String`.
@@ -402,23 +1424,33 @@ fn run_case(
 
         let msg = match payload {
             Some(payload) => format!("test panicked: {payload}"),
-            None => "test panicked".to_string(),
+            None => "test panicked".to_owned(),
         };
-        Err(RunError::fail(msg))
-    });
+        let location = PANIC_LOCATION.with(|l| l.take());
+        Err(RunError::fail(msg).with_location(location))
+    })
+}
 
-    let err = outcome.as_ref().err();
-    let status = err.map(|e| e.status());
-    let message = err.and_then(|e| e.cause().map(|c| c.to_string()));
-    notifier.notify(notify::Event::CaseComplete {
-        name: case.name().to_owned(),
-        mode: notify::RunMode::Test,
-        status,
-        message,
-        elapsed_s: Some(notify::Elapsed(timer.elapsed())),
-    })?;
+thread_local! {
+    static PANIC_LOCATION: std::cell::Cell<Option<String>> = const { std::cell::Cell::new(None) };
+}
 
-    Ok(status != Some(notify::RunStatus::Failed))
+/// Record where a panic happened so [`run_case`] can report it
+///
+/// The previous hook is still called so the default panic message is unaffected.  Only the first
+/// call installs the hook, so running several harnesses in one process doesn't stack them.
+fn install_panic_hook() {
+    static INSTALLED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+    INSTALLED.get_or_init(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            PANIC_LOCATION.with(|l| l.set(location));
+            previous(info);
+        }));
+    });
 }
 
 /// Fixed frame used to clean the backtrace with `RUST_BACKTRACE=1`.
@@ -430,15 +1462,279 @@ fn __rust_begin_short_backtrace<T, F: FnOnce() -> T>(f: F) -> T {
     std::hint::black_box(result)
 }
 
+/// Forward events from a worker, holding back [`notify::Event::CaseComplete`]
+///
+/// The worker sends the completion itself so it can account for panics that happen after the
+/// case reported its result.
 #[derive(Clone, Debug)]
 struct SenderNotifier {
     tx: std::sync::mpsc::Sender<notify::Event>,
+    complete: Option<notify::Event>,
 }
 
 impl notify::Notifier for SenderNotifier {
-    fn notify(&mut self, event: notify::Event) -> std::io::Result<()> {
-        // If the sender doesn't care, neither do we
-        let _ = self.tx.send(event);
+    fn notify(&mut self, event: &notify::Event) -> std::io::Result<()> {
+        if matches!(event, notify::Event::CaseComplete { .. }) {
+            self.complete = Some(event.clone());
+        } else {
+            // If the receiver doesn't care, neither do we
+            let _ = self.tx.send(event.clone());
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestCase {
+        name: String,
+        fail: bool,
+        /// Change the environment, see [`State::exclusive_env`]
+        env: bool,
+        ran_on: std::sync::Arc<std::sync::Mutex<Vec<std::thread::ThreadId>>>,
+    }
+
+    impl Case for TestCase {
+        fn name(&self) -> &str {
+            &self.name
+        }
+        fn kind(&self) -> TestKind {
+            Default::default()
+        }
+        fn mode(&self) -> RunMode {
+            RunMode::Test
+        }
+        fn source(&self) -> Option<&Source> {
+            None
+        }
+        fn exclusive(&self, state: &State) -> bool {
+            self.env && state.exclusive_env()
+        }
+        fn main_thread(&self) -> bool {
+            false
+        }
+
+        fn run(&self, state: &State) -> Result<(), RunError> {
+            self.ran_on
+                .lock()
+                .unwrap()
+                .push(std::thread::current().id());
+            if self.env {
+                let key = "LIBTEST2_HARNESS_ENV";
+                let inner = state.scoped_env(key, "outer", || {
+                    state.scoped_env(key, "inner", || std::env::var(key))
+                });
+                assert_eq!(inner.as_deref(), Ok("inner"));
+                assert_eq!(std::env::var_os(key), None);
+            }
+            if self.fail {
+                Err(RunError::fail("failed"))
+            } else {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                Ok(())
+            }
+        }
+    }
+
+    /// Panics outside of [`Case::run`], before anything is reported
+    struct BadTimeout;
+
+    impl Case for BadTimeout {
+        fn name(&self) -> &str {
+            "bad_timeout"
+        }
+        fn kind(&self) -> TestKind {
+            Default::default()
+        }
+        fn source(&self) -> Option<&Source> {
+            None
+        }
+        fn timeout(&self) -> Option<std::time::Duration> {
+            panic!("no timeout")
+        }
+        fn exclusive(&self, _: &State) -> bool {
+            false
+        }
+
+        fn run(&self, _: &State) -> Result<(), RunError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct EventLog(Vec<notify::Event>);
+
+    impl notify::Notifier for EventLog {
+        fn notify(&mut self, event: &notify::Event) -> std::io::Result<()> {
+            self.0.push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fail_fast_cancels_workers() {
+        let threads = 2;
+        let opts = libtest_lexarg::TestOpts {
+            run_tests: true,
+            fail_fast: true,
+            test_threads: std::num::NonZeroUsize::new(threads),
+            ..Default::default()
+        };
+        let cases = (0..20)
+            .map(|i| {
+                Box::new(TestCase {
+                    name: format!("case{i:02}"),
+                    fail: i == 0,
+                    ..Default::default()
+                }) as Box<dyn Case>
+            })
+            .collect();
+        let mut log = EventLog::default();
+
+        let success = run(&opts, None, cases, &mut log).unwrap();
+
+        assert!(!success);
+        let completed = log
+            .0
+            .iter()
+            .filter(|e| matches!(e, notify::Event::CaseComplete { .. }))
+            .count();
+        assert!(
+            (1..=threads).contains(&completed),
+            "{completed} cases completed"
+        );
+        assert!(matches!(
+            log.0.last(),
+            Some(notify::Event::SuiteComplete { .. })
+        ));
+    }
+
+    #[test]
+    fn panic_before_reporting_is_errored() {
+        let opts = libtest_lexarg::TestOpts {
+            run_tests: true,
+            test_threads: std::num::NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        let cases = vec![
+            Box::new(BadTimeout) as Box<dyn Case>,
+            Box::new(TestCase {
+                name: "passes".to_owned(),
+                ..Default::default()
+            }),
+        ];
+        let mut log = EventLog::default();
+
+        let success = run(&opts, None, cases, &mut log).unwrap();
+
+        assert!(!success);
+        let status = log.0.iter().find_map(|e| match e {
+            notify::Event::CaseComplete { name, status, .. } if name == "bad_timeout" => {
+                Some(*status)
+            }
+            _ => None,
+        });
+        assert_eq!(status, Some(Some(notify::RunStatus::Errored)));
+    }
+
+    #[test]
+    fn single_thread_spawns_nothing() {
+        let opts = resolve(libtest_lexarg::TestOpts {
+            test_threads: std::num::NonZeroUsize::new(1),
+            ..Default::default()
+        });
+        let ran_on = std::sync::Arc::default();
+        let cases = (0..3)
+            .map(|i| {
+                Box::new(TestCase {
+                    name: format!("case{i}"),
+                    ran_on: std::sync::Arc::clone(&ran_on),
+                    ..Default::default()
+                }) as Box<dyn Case>
+            })
+            .collect();
+        let mut log = EventLog::default();
+
+        let success = run(&opts, None, cases, &mut log).unwrap();
+
+        assert!(success);
+        let current = std::thread::current().id();
+        assert_eq!(*ran_on.lock().unwrap(), vec![current; 3]);
+    }
+
+    #[test]
+    fn exclusive_env_runs_alone() {
+        let opts = libtest_lexarg::TestOpts {
+            run_tests: true,
+            test_threads: std::num::NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        let ran_on = std::sync::Arc::default();
+        let cases = (0..3)
+            .map(|i| {
+                Box::new(TestCase {
+                    name: format!("case{i}"),
+                    env: i == 0,
+                    ran_on: std::sync::Arc::clone(&ran_on),
+                    ..Default::default()
+                }) as Box<dyn Case>
+            })
+            .collect();
+        let mut log = EventLog::default();
+
+        let success = run(&opts, None, cases, &mut log).unwrap();
+
+        assert!(success);
+        // Exclusive cases run after the others, on the calling thread
+        let current = std::thread::current().id();
+        assert_eq!(ran_on.lock().unwrap().last(), Some(&current));
+        let last = log.0.iter().rev().find_map(|e| match e {
+            notify::Event::CaseComplete { name, .. } => Some(name.as_str()),
+            _ => None,
+        });
+        assert_eq!(last, Some("case0"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "threads"))]
+    fn without_threads_feature_is_single_threaded() {
+        let opts = resolve(libtest_lexarg::TestOpts {
+            test_threads: std::num::NonZeroUsize::new(4),
+            ..Default::default()
+        });
+        assert_eq!(opts.test_threads, std::num::NonZeroUsize::new(1));
+    }
+
+    #[test]
+    fn long_flags_are_recognized() {
+        for flag in libtest_lexarg::LONG_FLAGS {
+            let raw = [format!("--{flag}")];
+            let mut parser = cli::Parser::new(&raw);
+            let arg = parser.next().unwrap();
+            let mut state = libtest_lexarg::TestOptsParseState::new();
+            let unrecognized = matches!(state.parse_next(&mut parser, arg), Ok(Some(_)));
+            assert!(!unrecognized, "`--{flag}` is not recognized");
+        }
+    }
+
+    #[test]
+    fn suggest_flags() {
+        assert_eq!(suggest_flag("formta"), Some("format"));
+        assert_eq!(suggest_flag("test-thread"), Some("test-threads"));
+        assert_eq!(suggest_flag("hepl"), Some("help"));
+        assert_eq!(suggest_flag("frobnicate"), None);
+    }
+
+    #[test]
+    fn split_quoted_args() {
+        assert_eq!(
+            split_args(" --skip  'slow case' \"a \\\"b\\\"\" c\\ d '' ").unwrap(),
+            ["--skip", "slow case", "a \"b\"", "c d", ""]
+        );
+        assert_eq!(split_args("--skip 'slow").unwrap_err(), "unterminated `'`");
+        assert_eq!(split_args("a\\").unwrap_err(), "trailing `\\`");
+    }
+}
@@ -0,0 +1,12 @@
+//! Hashing that is stable across platforms and Rust versions, for `--partition` and `--shuffle`
+
+/// A hash that, unlike [`std::collections::hash_map::DefaultHasher`], is stable across Rust
+/// versions
+pub(crate) fn fnv1a(name: &str) -> u64 {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
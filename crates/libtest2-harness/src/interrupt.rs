@@ -0,0 +1,91 @@
+//! Stop starting cases on Ctrl-C so the results so far can still be reported
+//!
+//! This is opt-in as catching the interrupt gets in the way of debuggers.  Only the first Ctrl-C
+//! is caught, so a second one still kills a hung case.
+
+static INTERRUPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Exit code after being interrupted, following the shell convention of `128 + SIGINT`
+pub(crate) const EXIT_CODE: i32 = 130;
+
+/// Whether Ctrl-C was pressed since [`install`]
+pub(crate) fn is_interrupted() -> bool {
+    INTERRUPTED.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Record the first Ctrl-C instead of exiting
+pub(crate) fn install() -> std::io::Result<()> {
+    imp::install()
+}
+
+fn interrupt() {
+    INTERRUPTED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+mod imp {
+    const SIGINT: std::os::raw::c_int = 2;
+    const SIG_DFL: usize = 0;
+    const SIG_ERR: usize = !0;
+
+    extern "C" {
+        fn signal(signum: std::os::raw::c_int, handler: usize) -> usize;
+    }
+
+    extern "C" fn on_sigint(_signum: std::os::raw::c_int) {
+        // Only async-signal-safe operations, like atomics and `signal`, are allowed here
+        super::interrupt();
+        // SAFETY: restoring the default handler doesn't run any code
+        unsafe { signal(SIGINT, SIG_DFL) };
+    }
+
+    pub(super) fn install() -> std::io::Result<()> {
+        let handler = on_sigint as extern "C" fn(std::os::raw::c_int);
+        // SAFETY: `on_sigint` only stores to an atomic and restores the default handler
+        let previous = unsafe { signal(SIGINT, handler as usize) };
+        if previous == SIG_ERR {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    const CTRL_C_EVENT: u32 = 0;
+
+    type HandlerRoutine = unsafe extern "system" fn(ctrl_type: u32) -> i32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetConsoleCtrlHandler(handler: Option<HandlerRoutine>, add: i32) -> i32;
+    }
+
+    unsafe extern "system" fn on_ctrl(ctrl_type: u32) -> i32 {
+        // Leave later ones to the default handler, which exits
+        if ctrl_type == CTRL_C_EVENT && !super::is_interrupted() {
+            super::interrupt();
+            1
+        } else {
+            0
+        }
+    }
+
+    pub(super) fn install() -> std::io::Result<()> {
+        // SAFETY: `on_ctrl` only stores to an atomic
+        let added = unsafe { SetConsoleCtrlHandler(Some(on_ctrl), 1) };
+        if added == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    pub(super) fn install() -> std::io::Result<()> {
+        Err(std::io::Error::other(
+            "catching interrupts is not supported on this platform",
+        ))
+    }
+}
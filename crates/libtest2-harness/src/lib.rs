@@ -19,18 +19,50 @@
 //!     .main();
 //! ```
 //!
+//! # Threads
+//!
+//! With the `threads` feature, cases run in parallel on worker threads, see `--test-threads`.
+//! Without it, on platforms without threads, or with `--test-threads=1`, every case runs in turn
+//! on the thread that called [`Harness::main`] and no threads are spawned.
+//!
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "async")]
+mod async_case;
+#[cfg(feature = "tracing")]
+mod capture;
 mod case;
+mod conclusion;
+mod diff;
 mod harness;
+mod hash;
+mod interrupt;
 mod notify;
+mod partition;
+mod repeat;
 mod shuffle;
 mod state;
+mod subprocess;
 
 pub mod cli;
 
+#[cfg(feature = "async")]
+pub use async_case::AsyncCase;
+#[cfg(feature = "async")]
+pub use async_case::Executor;
+#[cfg(feature = "async")]
+pub use async_case::RunFuture;
 pub use case::*;
+pub use conclusion::*;
+pub use diff::Diff;
 pub use harness::*;
+pub use libtest_lexarg::OutputFormat;
+pub use libtest_lexarg::TestOpts;
+pub use notify::Elapsed;
+pub use notify::Event;
+pub use notify::OutputStream;
 pub use notify::RunMode;
+pub use notify::RunStatus;
+pub use notify::Timings;
 pub use state::*;
@@ -0,0 +1,62 @@
+use super::Event;
+
+/// Show what each case would do for `--dry-run`, without running any
+#[derive(Debug)]
+pub(crate) struct DryRunNotifier<W> {
+    writer: W,
+    run_ignored: bool,
+    num_run: usize,
+    num_ignored: usize,
+    num_filtered_out: usize,
+}
+
+impl<W: std::io::Write> DryRunNotifier<W> {
+    /// `run_ignored` is from `--include-ignored` or `--ignored`
+    pub(crate) fn new(writer: W, run_ignored: bool) -> Self {
+        Self {
+            writer,
+            run_ignored,
+            num_run: 0,
+            num_ignored: 0,
+            num_filtered_out: 0,
+        }
+    }
+}
+
+impl<W: std::io::Write> super::Notifier for DryRunNotifier<W> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        match event {
+            Event::DiscoverStart => {}
+            Event::DiscoverCase {
+                name, run, ignored, ..
+            } => {
+                let status = if !run {
+                    self.num_filtered_out += 1;
+                    "filtered"
+                } else if *ignored && !self.run_ignored {
+                    self.num_ignored += 1;
+                    "ignored"
+                } else {
+                    self.num_run += 1;
+                    "will run"
+                };
+                writeln!(self.writer, "{name} ({status})")?;
+            }
+            Event::DiscoverComplete { .. } => {
+                writeln!(self.writer)?;
+                writeln!(
+                    self.writer,
+                    "{} will run; {} ignored; {} filtered out",
+                    self.num_run, self.num_ignored, self.num_filtered_out
+                )?;
+            }
+            Event::SuiteStart { .. } => {}
+            Event::CaseStart { .. } => {}
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete { .. } => {}
+            Event::CaseComplete { .. } => {}
+            Event::SuiteComplete { .. } => {}
+        }
+        Ok(())
+    }
+}
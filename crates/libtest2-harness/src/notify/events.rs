@@ -0,0 +1,19 @@
+use super::Event;
+
+/// Collect events in memory, for [`crate::Harness::run_to_events`]
+pub(crate) struct EventsNotifier<'e> {
+    events: &'e mut Vec<Event>,
+}
+
+impl<'e> EventsNotifier<'e> {
+    pub(crate) fn new(events: &'e mut Vec<Event>) -> Self {
+        Self { events }
+    }
+}
+
+impl super::Notifier for EventsNotifier<'_> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        self.events.push(event.clone());
+        Ok(())
+    }
+}
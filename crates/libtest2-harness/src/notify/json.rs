@@ -3,18 +3,57 @@ use super::Event;
 #[derive(Debug)]
 pub(crate) struct JsonNotifier<W> {
     writer: W,
+    suite: String,
+    pretty: bool,
+    quiet: bool,
 }
 
 impl<W: std::io::Write> JsonNotifier<W> {
-    pub(crate) fn new(writer: W) -> Self {
-        Self { writer }
+    /// `suite` is included in every event so streams from several suites can be told apart
+    pub(crate) fn new(writer: W, suite: String) -> Self {
+        Self {
+            writer,
+            suite,
+            pretty: false,
+            quiet: false,
+        }
+    }
+
+    /// Indent each event across multiple lines
+    pub(crate) fn pretty(mut self, yes: bool) -> Self {
+        self.pretty = yes;
+        self
+    }
+
+    /// Leave out [`Event::DiscoverCase`], keeping the counts from [`Event::DiscoverComplete`]
+    pub(crate) fn quiet(mut self, yes: bool) -> Self {
+        self.quiet = yes;
+        self
     }
 }
 
 impl<W: std::io::Write> super::Notifier for JsonNotifier<W> {
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
-        let event = serde_json::to_string(&event)?;
-        writeln!(self.writer, "{}", event)?;
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        if self.quiet && matches!(event, Event::DiscoverCase { .. }) {
+            return Ok(());
+        }
+        let record = Record {
+            event,
+            suite: &self.suite,
+        };
+        let record = if self.pretty {
+            serde_json::to_string_pretty(&record)?
+        } else {
+            serde_json::to_string(&record)?
+        };
+        writeln!(self.writer, "{}", record)?;
         Ok(())
     }
 }
+
+#[derive(serde::Serialize)]
+struct Record<'e> {
+    #[serde(flatten)]
+    event: &'e Event,
+    suite: &'e str,
+}
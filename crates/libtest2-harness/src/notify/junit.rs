@@ -5,6 +5,8 @@ use super::RunStatus;
 pub(crate) struct JunitRunNotifier<W> {
     writer: W,
     events: Vec<Event>,
+    started: Option<std::time::SystemTime>,
+    root_classname: Option<String>,
 }
 
 impl<W: std::io::Write> JunitRunNotifier<W> {
@@ -12,17 +14,38 @@ impl<W: std::io::Write> JunitRunNotifier<W> {
         Self {
             writer,
             events: Vec::new(),
+            started: None,
+            root_classname: None,
         }
     }
+
+    /// Classname for cases that aren't in a module
+    ///
+    /// Defaults to the suite name from [`crate::Harness::suite_name`], falling back to `crate`.
+    pub(crate) fn root_classname(mut self, classname: Option<String>) -> Self {
+        self.root_classname = classname;
+        self
+    }
 }
 
 impl<W: std::io::Write> super::Notifier for JunitRunNotifier<W> {
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
-        let finished = matches!(&event, Event::SuiteComplete { .. });
-        self.events.push(event);
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        if let Event::SuiteStart { suite } = event {
+            self.started = Some(std::time::SystemTime::now());
+            if self.root_classname.is_none() {
+                self.root_classname = suite.clone();
+            }
+        }
+        let finished = matches!(event, Event::SuiteComplete { .. });
+        self.events.push(event.clone());
         if finished {
+            let timestamp = iso8601(self.started.unwrap_or_else(std::time::SystemTime::now));
+            let hostname = escape(&hostname());
+            let root_classname = self.root_classname.as_deref().unwrap_or("crate");
+            let mut suite_elapsed_s = std::time::Duration::ZERO;
             let mut num_run = 0;
             let mut num_failed = 0;
+            let mut num_errored = 0;
             let mut num_ignored = 0;
             for event in &self.events {
                 match event {
@@ -33,20 +56,28 @@ impl<W: std::io::Write> super::Notifier for JunitRunNotifier<W> {
                         }
                     }
                     Event::DiscoverComplete { .. } => {}
-                    Event::SuiteStart => {}
+                    Event::SuiteStart { .. } => {}
                     Event::CaseStart { .. } => {}
+                    Event::CaseOutput { .. } => {}
+                    Event::BenchComplete { .. } => {}
                     Event::CaseComplete { status, .. } => match status {
-                        Some(RunStatus::Ignored) => {
+                        Some(RunStatus::Ignored) | Some(RunStatus::Skipped) => {
                             num_ignored += 1;
                         }
                         Some(RunStatus::Failed) => {
                             num_failed += 1;
                         }
+                        Some(RunStatus::Errored) => {
+                            num_errored += 1;
+                        }
                         None => {}
                     },
-                    Event::SuiteComplete { .. } => {}
+                    Event::SuiteComplete { elapsed_s, .. } => {
+                        suite_elapsed_s = elapsed_s.0;
+                    }
                 }
             }
+            let suite_elapsed_s = suite_elapsed_s.as_secs_f64();
 
             writeln!(self.writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
             writeln!(self.writer, "<testsuites>")?;
@@ -55,9 +86,12 @@ impl<W: std::io::Write> super::Notifier for JunitRunNotifier<W> {
                 self.writer,
                 "<testsuite name=\"test\" package=\"test\" id=\"0\" \
              tests=\"{num_run}\" \
-             errors=\"0\" \
+             errors=\"{num_errored}\" \
              failures=\"{num_failed}\" \
              skipped=\"{num_ignored}\" \
+             time=\"{suite_elapsed_s:.3}\" \
+             timestamp=\"{timestamp}\" \
+             hostname=\"{hostname}\" \
              >"
             )?;
             for event in std::mem::take(&mut self.events) {
@@ -69,23 +103,42 @@ impl<W: std::io::Write> super::Notifier for JunitRunNotifier<W> {
                     ..
                 } = event
                 {
-                    let (class_name, test_name) = parse_class_name(&name);
+                    let (class_name, test_name) = parse_class_name(&name, root_classname);
+                    let class_name = escape(&class_name);
+                    let test_name = escape(&test_name);
                     let elapsed_s = elapsed_s.unwrap_or_default();
                     match status {
-                        Some(RunStatus::Ignored) => {}
-                        Some(RunStatus::Failed) => {
+                        Some(RunStatus::Ignored) | Some(RunStatus::Skipped) => {
+                            // Viewers show these greyed out rather than leaving them out
+                            writeln!(
+                                self.writer,
+                                "<testcase classname=\"{class_name}\" \
+                         name=\"{test_name}\" time=\"{elapsed_s}\">",
+                            )?;
+                            match message.filter(|m| !m.is_empty()) {
+                                Some(message) => {
+                                    let message = escape(&message);
+                                    writeln!(self.writer, "<skipped message=\"{message}\"/>")?;
+                                }
+                                None => writeln!(self.writer, "<skipped/>")?,
+                            }
+                            writeln!(self.writer, "</testcase>")?;
+                        }
+                        Some(status @ (RunStatus::Failed | RunStatus::Errored)) => {
+                            let (tag, kind) = match status {
+                                RunStatus::Errored => ("error", "error"),
+                                _ => ("failure", "assert"),
+                            };
                             writeln!(
                                 self.writer,
                                 "<testcase classname=\"{class_name}\" \
                          name=\"{test_name}\" time=\"{elapsed_s}\">",
                             )?;
                             if let Some(message) = message {
-                                writeln!(
-                                    self.writer,
-                                    "<failure message=\"{message}\" type=\"assert\"/>"
-                                )?;
+                                let message = cdata(&message);
+                                writeln!(self.writer, "<{tag} type=\"{kind}\">{message}</{tag}>")?;
                             } else {
-                                writeln!(self.writer, "<failure type=\"assert\"/>")?;
+                                writeln!(self.writer, "<{tag} type=\"{kind}\"/>")?;
                             }
                             writeln!(self.writer, "</testcase>")?;
                         }
@@ -108,14 +161,100 @@ impl<W: std::io::Write> super::Notifier for JunitRunNotifier<W> {
     }
 }
 
-fn parse_class_name(name: &str) -> (String, String) {
+/// Format as ISO-8601 in UTC, e.g. `2024-01-31T13:59:59`
+fn iso8601(time: std::time::SystemTime) -> String {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86400) as i64;
+    let secs_of_day = secs % 86400;
+    let (hour, minute, second) = (
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+    );
+
+    // `civil_from_days` from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}")
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|h| h.trim().to_owned())
+        })
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_owned())
+}
+
+/// Escape `text` for use in XML attributes and text
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Wrap `text` so it is not interpreted as XML
+fn cdata(text: &str) -> String {
+    // `]]>` can't appear in a CDATA section, so split it across two sections
+    let text = text.replace("]]>", "]]]]><![CDATA[>");
+    format!("<![CDATA[{text}]]>")
+}
+
+fn parse_class_name(name: &str, root_classname: &str) -> (String, String) {
     // Module path => classname
     // Function name => name
     let module_segments: Vec<&str> = name.split("::").collect();
     let (class_name, test_name) = match module_segments[..] {
-        [test] => (String::from("crate"), String::from(test)),
+        [test] => (String::from(root_classname), String::from(test)),
         [ref path @ .., test] => (path.join("::"), String::from(test)),
         [..] => unreachable!(),
     };
     (class_name, test_name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso8601_format() {
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(951_782_400 + 3723);
+        assert_eq!(iso8601(time), "2000-02-29T01:02:03");
+        assert_eq!(iso8601(std::time::UNIX_EPOCH), "1970-01-01T00:00:00");
+    }
+
+    #[test]
+    fn escape_markup() {
+        assert_eq!(escape(r#"a<b>&"c'"#), "a&lt;b&gt;&amp;&quot;c&apos;");
+    }
+
+    #[test]
+    fn cdata_escapes_terminator() {
+        assert_eq!(cdata("a]]>b"), "<![CDATA[a]]]]><![CDATA[>b]]>");
+    }
+}
@@ -1,56 +1,146 @@
+mod dry_run;
+mod events;
 #[cfg(feature = "json")]
 mod json;
 #[cfg(feature = "junit")]
 mod junit;
 mod pretty;
+mod progress;
+#[cfg(feature = "json")]
+mod results;
 mod summary;
+mod tee;
 mod terse;
 
+pub(crate) use dry_run::*;
+pub(crate) use events::*;
 #[cfg(feature = "json")]
 pub(crate) use json::*;
 #[cfg(feature = "junit")]
 pub(crate) use junit::*;
 pub(crate) use pretty::*;
+pub(crate) use progress::*;
+#[cfg(feature = "json")]
+pub(crate) use results::*;
 pub(crate) use summary::*;
+pub(crate) use tee::*;
 pub(crate) use terse::*;
 
 pub(crate) trait Notifier {
     fn threaded(&mut self, _yes: bool) {}
 
-    fn notify(&mut self, event: Event) -> std::io::Result<()>;
+    fn notify(&mut self, event: &Event) -> std::io::Result<()>;
 }
 
+/// What happened during a run, see [`crate::Harness::run_to_events`]
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(serde::Serialize))]
 #[cfg_attr(feature = "json", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "json", serde(tag = "event"))]
-pub(crate) enum Event {
+// Events are short-lived, so boxing `CaseComplete` isn't worth the churn
+#[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
+pub enum Event {
     DiscoverStart,
+    /// A case was found, whether or not it will be run
+    #[non_exhaustive]
     DiscoverCase {
         name: String,
         mode: RunMode,
         run: bool,
+        ignored: bool,
+        /// From [`crate::Case::describe`]
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+        description: Option<String>,
     },
+    #[non_exhaustive]
     DiscoverComplete {
-        #[allow(dead_code)]
         elapsed_s: Elapsed,
+        /// Used to shuffle the cases, from `--shuffle`
         seed: Option<u64>,
+        /// Number of cases that will be run
+        num_to_run: usize,
+        /// Number of cases excluded by filters
+        num_filtered_out: usize,
     },
-    SuiteStart,
+    #[non_exhaustive]
+    SuiteStart {
+        /// Set by [`crate::Harness::suite_name`]
+        ///
+        /// `--format=json` reports the suite on every event instead.
+        #[cfg_attr(feature = "json", serde(skip))]
+        suite: Option<String>,
+    },
+    #[non_exhaustive]
     CaseStart {
         name: String,
+        /// When the case started, relative to the start of the run, from `--timeline`
+        #[cfg(feature = "json")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        at_s: Option<Elapsed>,
+    },
+    /// A line the case wrote, with `--nocapture`, `--format=json`, and `--run-in-subprocess`
+    ///
+    /// Lines of a stream are in the order they were written, but stdout and stderr are read
+    /// separately, so how they interleave is approximate.  With parallel cases, lines of different
+    /// cases interleave too, so tell them apart by `name`.
+    #[non_exhaustive]
+    CaseOutput {
+        name: String,
+        stream: OutputStream,
+        line: String,
+    },
+    /// Measurements from [`crate::State::record_bench`], sent before the case's
+    /// [`Event::CaseComplete`]
+    #[non_exhaustive]
+    BenchComplete {
+        name: String,
+        /// Median of `samples`
+        ns_per_iter: u64,
+        /// Nanoseconds per iteration for each sample
+        samples: Vec<u64>,
     },
+    #[non_exhaustive]
     CaseComplete {
         name: String,
-        #[allow(dead_code)]
         mode: RunMode,
+        /// `None` when the case passed
         status: Option<RunStatus>,
+        /// Why the case failed, was ignored, or was skipped
         message: Option<String>,
-        #[allow(dead_code)]
         elapsed_s: Option<Elapsed>,
+        /// Timing of each run, from `--iterations`
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+        timings: Option<Timings>,
+        /// From [`crate::State::record`]
+        #[cfg_attr(
+            feature = "json",
+            serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")
+        )]
+        metadata: std::collections::BTreeMap<String, String>,
+        /// `tracing` events of a failed case, with the `tracing` feature
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+        logs: Option<String>,
+        /// Structured form of `message`, when available
+        #[cfg_attr(feature = "json", serde(skip))]
+        diff: Option<crate::diff::Diff>,
+        /// Where the case is defined, as `path[:line:col]`
+        #[cfg_attr(feature = "json", serde(skip))]
+        location: Option<String>,
+        /// Where the case failed, from a panic or [`crate::RunError::at`]
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+        failure_location: Option<String>,
+        /// When the case completed, relative to the start of the run, from `--timeline`
+        #[cfg(feature = "json")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        at_s: Option<Elapsed>,
     },
+    #[non_exhaustive]
     SuiteComplete {
         elapsed_s: Elapsed,
+        /// `--fail-fast` stopped the run after a failure
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "std::ops::Not::not"))]
+        aborted: bool,
     },
 }
 
@@ -72,18 +162,41 @@ impl RunMode {
     }
 }
 
+/// Where [`Event::CaseOutput`] was written
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "json", derive(serde::Serialize))]
 #[cfg_attr(feature = "json", serde(rename_all = "kebab-case"))]
-pub(crate) enum RunStatus {
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// How a case that didn't pass turned out
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "kebab-case"))]
+#[non_exhaustive]
+pub enum RunStatus {
+    /// See [`crate::State::ignore`]
     Ignored,
+    /// See [`crate::State::skip`]
+    Skipped,
     Failed,
+    /// The case could not be run, like from a setup problem
+    Errored,
+}
+
+impl RunStatus {
+    /// Whether the run as a whole failed
+    pub(crate) fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed | Self::Errored)
+    }
 }
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "json", derive(serde::Serialize))]
 #[cfg_attr(feature = "json", serde(into = "String"))]
-pub(crate) struct Elapsed(pub std::time::Duration);
+pub struct Elapsed(pub std::time::Duration);
 
 impl std::fmt::Display for Elapsed {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -97,9 +210,46 @@ impl From<Elapsed> for String {
     }
 }
 
+/// Aggregate timing of a case that was run multiple times
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct Timings {
+    pub iterations: usize,
+    pub min_s: Elapsed,
+    pub median_s: Elapsed,
+    pub max_s: Elapsed,
+}
+
+impl Timings {
+    /// `None` when there is nothing to aggregate
+    pub(crate) fn new(mut durations: Vec<std::time::Duration>) -> Option<Self> {
+        if durations.len() < 2 {
+            return None;
+        }
+        durations.sort_unstable();
+        Some(Self {
+            iterations: durations.len(),
+            min_s: Elapsed(durations[0]),
+            median_s: Elapsed(durations[durations.len() / 2]),
+            max_s: Elapsed(durations[durations.len() - 1]),
+        })
+    }
+}
+
 const FAILED: anstyle::Style =
     anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Red)));
 const OK: anstyle::Style =
     anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Green)));
 const IGNORED: anstyle::Style =
     anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Yellow)));
+const DIFF_EXPECTED: anstyle::Style =
+    anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Red)));
+const DIFF_ACTUAL: anstyle::Style =
+    anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Green)));
+const ERRORED: anstyle::Style = anstyle::Style::new()
+    .fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Red)))
+    .bold();
+const SKIPPED: anstyle::Style = anstyle::Style::new()
+    .fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Yellow)))
+    .dimmed();
@@ -1,8 +1,100 @@
 use super::Event;
 use super::RunStatus;
+use super::ERRORED;
 use super::FAILED;
 use super::IGNORED;
 use super::OK;
+use super::SKIPPED;
+
+/// List cases grouped by their `::`-separated module path
+#[derive(Debug)]
+pub(crate) struct PrettyListNotifier<W> {
+    writer: W,
+    root: ListNode,
+    tests: usize,
+}
+
+impl<W: std::io::Write> PrettyListNotifier<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            root: Default::default(),
+            tests: 0,
+        }
+    }
+}
+
+impl<W: std::io::Write> super::Notifier for PrettyListNotifier<W> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        match event {
+            Event::DiscoverStart => {}
+            Event::DiscoverCase {
+                name,
+                mode,
+                run,
+                ignored,
+                ..
+            } => {
+                if *run {
+                    let mode = mode.as_str();
+                    let ignored = if *ignored { " (ignored)" } else { "" };
+                    self.root.insert(name, format!("{mode}{ignored}"));
+                    self.tests += 1;
+                }
+            }
+            Event::DiscoverComplete { .. } => {
+                self.root.write(&mut self.writer, 0)?;
+                writeln!(self.writer)?;
+                writeln!(self.writer, "{} tests", self.tests)?;
+                writeln!(self.writer)?;
+            }
+            Event::SuiteStart { .. } => {}
+            Event::CaseStart { .. } => {}
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete { .. } => {}
+            Event::CaseComplete { .. } => {}
+            Event::SuiteComplete { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// Cases and modules in the order they were first seen
+#[derive(Default, Debug)]
+struct ListNode {
+    cases: Vec<(String, String)>,
+    modules: Vec<(String, ListNode)>,
+}
+
+impl ListNode {
+    fn insert(&mut self, name: &str, description: String) {
+        match name.split_once("::") {
+            Some((module, rest)) => {
+                let index = match self.modules.iter().position(|(m, _)| m == module) {
+                    Some(index) => index,
+                    None => {
+                        self.modules.push((module.to_owned(), Default::default()));
+                        self.modules.len() - 1
+                    }
+                };
+                self.modules[index].1.insert(rest, description);
+            }
+            None => self.cases.push((name.to_owned(), description)),
+        }
+    }
+
+    fn write(&self, writer: &mut dyn std::io::Write, depth: usize) -> std::io::Result<()> {
+        let indent = "    ".repeat(depth);
+        for (module, node) in &self.modules {
+            writeln!(writer, "{indent}{module}::")?;
+            node.write(writer, depth + 1)?;
+        }
+        for (name, description) in &self.cases {
+            writeln!(writer, "{indent}{name}: {description}")?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub(crate) struct PrettyRunNotifier<W> {
@@ -10,6 +102,9 @@ pub(crate) struct PrettyRunNotifier<W> {
     is_multithreaded: bool,
     summary: super::Summary,
     name_width: usize,
+    report_time: bool,
+    /// Measurements to show in place of `ok`, by case
+    benches: std::collections::HashMap<String, String>,
 }
 
 impl<W: std::io::Write> PrettyRunNotifier<W> {
@@ -19,8 +114,34 @@ impl<W: std::io::Write> PrettyRunNotifier<W> {
             is_multithreaded: false,
             summary: Default::default(),
             name_width: 0,
+            report_time: false,
+            benches: Default::default(),
         }
     }
+
+    /// Show how long each case took, from `--report-time`
+    pub(crate) fn report_time(mut self, yes: bool) -> Self {
+        self.report_time = yes;
+        self
+    }
+
+    /// End with a machine-readable result line, see [`super::Summary::result_line`]
+    pub(crate) fn result_line(mut self, yes: bool) -> Self {
+        self.summary.result_line = yes;
+        self
+    }
+
+    /// Show results per module, see [`super::Summary::subtotals`]
+    pub(crate) fn subtotals(mut self, yes: bool) -> Self {
+        self.summary.subtotals = yes.then(Default::default);
+        self
+    }
+
+    /// Mark changes since `--baseline`, see [`super::Summary::baseline`]
+    pub(crate) fn baseline(mut self, baseline: Option<std::collections::BTreeSet<String>>) -> Self {
+        self.summary.baseline = baseline;
+        self
+    }
 }
 
 impl<W: std::io::Write> super::Notifier for PrettyRunNotifier<W> {
@@ -28,17 +149,17 @@ impl<W: std::io::Write> super::Notifier for PrettyRunNotifier<W> {
         self.is_multithreaded = yes;
     }
 
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
-        self.summary.notify(event.clone())?;
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        self.summary.notify(event)?;
         match event {
             Event::DiscoverStart => {}
             Event::DiscoverCase { name, run, .. } => {
-                if run {
+                if *run {
                     self.name_width = name.len().max(self.name_width);
                 }
             }
             Event::DiscoverComplete { .. } => {}
-            Event::SuiteStart => {
+            Event::SuiteStart { .. } => {
                 self.summary.write_start(&mut self.writer)?;
             }
             Event::CaseStart { name, .. } => {
@@ -47,17 +168,63 @@ impl<W: std::io::Write> super::Notifier for PrettyRunNotifier<W> {
                     self.writer.flush()?;
                 }
             }
-            Event::CaseComplete { name, status, .. } => {
-                let (s, style) = match status {
-                    Some(RunStatus::Ignored) => ("ignored", IGNORED),
-                    Some(RunStatus::Failed) => ("FAILED", FAILED),
-                    None => ("ok", OK),
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete {
+                name,
+                ns_per_iter,
+                samples,
+            } => {
+                let min = samples.iter().min().copied().unwrap_or_default();
+                let max = samples.iter().max().copied().unwrap_or_default();
+                let ns_per_iter = thousands(*ns_per_iter);
+                let deviation = thousands(max - min);
+                self.benches.insert(
+                    name.clone(),
+                    format!("bench: {ns_per_iter:>11} ns/iter (+/- {deviation})"),
+                );
+            }
+            Event::CaseComplete {
+                name,
+                status,
+                message,
+                elapsed_s,
+                timings,
+                ..
+            } => {
+                let bench = self.benches.remove(name);
+                let (s, style) = match (status, &bench) {
+                    (Some(RunStatus::Ignored), _) => ("ignored", IGNORED),
+                    (Some(RunStatus::Skipped), _) => ("skipped", SKIPPED),
+                    (Some(RunStatus::Failed), _) => ("FAILED", FAILED),
+                    (Some(RunStatus::Errored), _) => ("ERRORED", ERRORED),
+                    (None, Some(bench)) => (bench.as_str(), OK),
+                    (None, None) => ("ok", OK),
+                };
+                let reason = match (status, message) {
+                    (Some(RunStatus::Ignored), Some(message)) if !message.is_empty() => {
+                        format!(", {message}")
+                    }
+                    (Some(RunStatus::Skipped), Some(message)) => format!(" ({message})"),
+                    _ => String::new(),
+                };
+                let time = match (self.report_time, timings, elapsed_s) {
+                    (true, Some(timings), _) => format!(
+                        " <min {}, median {}, max {}>",
+                        timings.min_s, timings.median_s, timings.max_s
+                    ),
+                    (true, None, Some(elapsed_s)) => format!(" <{elapsed_s}>"),
+                    _ => String::new(),
                 };
 
                 if self.is_multithreaded {
                     write!(self.writer, "test {: <1$} ... ", name, self.name_width)?;
                 }
-                writeln!(self.writer, "{}{s}{}", style.render(), style.render_reset())?;
+                writeln!(
+                    self.writer,
+                    "{}{s}{reason}{}{time}",
+                    style.render(),
+                    style.render_reset()
+                )?;
             }
             Event::SuiteComplete { .. } => {
                 self.summary.write_complete(&mut self.writer)?;
@@ -66,3 +233,28 @@ impl<W: std::io::Write> super::Notifier for PrettyRunNotifier<W> {
         Ok(())
     }
 }
+
+/// Group digits by thousands, like `1,234,567`
+fn thousands(mut n: u64) -> String {
+    let mut groups = Vec::new();
+    while 1000 <= n {
+        groups.push(format!("{:03}", n % 1000));
+        n /= 1000;
+    }
+    groups.push(n.to_string());
+    groups.reverse();
+    groups.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thousands_separators() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(1000), "1,000");
+        assert_eq!(thousands(1234567), "1,234,567");
+    }
+}
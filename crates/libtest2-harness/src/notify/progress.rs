@@ -0,0 +1,129 @@
+use super::Event;
+
+/// How often the status line may be redrawn
+const INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Erase the current line and move to its start
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Keep a `running 45/1000 (3 failed)` status line updated for `--progress`
+///
+/// This redraws the line in place, so `writer` should be a terminal that nothing else writes to.
+#[derive(Debug)]
+pub(crate) struct ProgressNotifier<W> {
+    writer: W,
+    summary: super::Summary,
+    last_draw: Option<std::time::Instant>,
+}
+
+impl<W: std::io::Write> ProgressNotifier<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            summary: Default::default(),
+            last_draw: None,
+        }
+    }
+
+    fn draw(&mut self) -> std::io::Result<()> {
+        let summary = &self.summary;
+        let completed = summary.num_passed
+            + summary.num_failed
+            + summary.num_errored
+            + summary.num_ignored
+            + summary.num_skipped;
+        let failed = summary.num_failed + summary.num_errored;
+        write!(
+            self.writer,
+            "{CLEAR_LINE}running {completed}/{} ({failed} failed)",
+            summary.num_run
+        )?;
+        self.writer.flush()?;
+        self.last_draw = Some(std::time::Instant::now());
+        Ok(())
+    }
+
+    fn clear(&mut self) -> std::io::Result<()> {
+        if self.last_draw.take().is_some() {
+            write!(self.writer, "{CLEAR_LINE}")?;
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: std::io::Write> super::Notifier for ProgressNotifier<W> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        self.summary.notify(event)?;
+        match event {
+            Event::SuiteStart { .. } => self.draw()?,
+            Event::CaseComplete { .. }
+                if self
+                    .last_draw
+                    .map(|t| INTERVAL <= t.elapsed())
+                    .unwrap_or(true) =>
+            {
+                self.draw()?;
+            }
+            Event::SuiteComplete { .. } => self.clear()?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Notifier as _;
+    use super::*;
+
+    #[test]
+    fn status_line() {
+        let mut notifier = ProgressNotifier::new(Vec::new());
+        for name in ["cat", "dog"] {
+            notifier
+                .notify(&Event::DiscoverCase {
+                    name: name.to_owned(),
+                    mode: Default::default(),
+                    run: true,
+                    ignored: false,
+                    description: None,
+                })
+                .unwrap();
+        }
+        notifier.notify(&Event::SuiteStart { suite: None }).unwrap();
+        // Skip throttling
+        notifier.last_draw = None;
+        notifier
+            .notify(&Event::CaseComplete {
+                name: "cat".to_owned(),
+                mode: Default::default(),
+                status: Some(super::super::RunStatus::Failed),
+                message: None,
+                elapsed_s: None,
+                timings: None,
+                metadata: Default::default(),
+                logs: None,
+                diff: None,
+                location: None,
+                failure_location: None,
+                #[cfg(feature = "json")]
+                at_s: None,
+            })
+            .unwrap();
+        notifier
+            .notify(&Event::SuiteComplete {
+                elapsed_s: Default::default(),
+                aborted: false,
+            })
+            .unwrap();
+
+        let output = String::from_utf8(notifier.writer).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "{CLEAR_LINE}running 0/2 (0 failed){CLEAR_LINE}running 1/2 (1 failed){CLEAR_LINE}"
+            ),
+        );
+    }
+}
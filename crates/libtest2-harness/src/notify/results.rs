@@ -0,0 +1,87 @@
+use super::Event;
+use super::RunStatus;
+
+/// Save the outcome of each case for a later `--baseline`
+#[derive(Debug)]
+pub(crate) struct ResultsNotifier {
+    path: std::path::PathBuf,
+    results: Results,
+}
+
+impl ResultsNotifier {
+    pub(crate) fn new(path: std::path::PathBuf) -> Self {
+        Self {
+            path,
+            results: Default::default(),
+        }
+    }
+}
+
+impl super::Notifier for ResultsNotifier {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        match event {
+            Event::CaseComplete {
+                name,
+                status,
+                elapsed_s,
+                ..
+            } => {
+                let status = match status {
+                    Some(RunStatus::Ignored) => "ignored",
+                    Some(RunStatus::Skipped) => "skipped",
+                    Some(RunStatus::Failed) => "failed",
+                    Some(RunStatus::Errored) => "errored",
+                    None => "ok",
+                };
+                self.results.cases.insert(
+                    name.clone(),
+                    CaseResult {
+                        status: status.to_owned(),
+                        elapsed_s: elapsed_s.map(String::from),
+                    },
+                );
+            }
+            Event::SuiteComplete { .. } => {
+                let results = serde_json::to_string_pretty(&self.results)?;
+                std::fs::write(&self.path, results).map_err(|e| {
+                    std::io::Error::new(
+                        e.kind(),
+                        format!("could not write results to `{}`: {e}", self.path.display()),
+                    )
+                })?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Names of the cases that failed in results saved by [`ResultsNotifier`]
+pub(crate) fn load_baseline(
+    path: &std::path::Path,
+) -> std::io::Result<std::collections::BTreeSet<String>> {
+    let error = |e: &dyn std::fmt::Display| {
+        std::io::Error::other(format!("could not read baseline `{}`: {e}", path.display()))
+    };
+    let results = std::fs::read_to_string(path).map_err(|e| error(&e))?;
+    let results: Results = serde_json::from_str(&results).map_err(|e| error(&e))?;
+    let failures = results
+        .cases
+        .into_iter()
+        .filter(|(_, case)| matches!(case.status.as_str(), "failed" | "errored"))
+        .map(|(name, _)| name)
+        .collect();
+    Ok(failures)
+}
+
+#[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
+struct Results {
+    cases: std::collections::BTreeMap<String, CaseResult>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CaseResult {
+    status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elapsed_s: Option<String>,
+}
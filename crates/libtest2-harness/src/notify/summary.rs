@@ -1,13 +1,25 @@
 use super::Event;
 use super::RunStatus;
+use super::DIFF_ACTUAL;
+use super::DIFF_EXPECTED;
 use super::FAILED;
 use super::OK;
+use crate::diff::Diff;
+use crate::diff::DiffLine;
 
 #[derive(Default, Clone, Debug)]
 pub(crate) struct Summary {
+    pub(crate) suite: Option<String>,
     pub(crate) seed: Option<u64>,
     pub(crate) failures: std::collections::BTreeMap<String, Option<String>>,
+    pub(crate) diffs: std::collections::BTreeMap<String, Diff>,
+    /// From [`crate::Case::describe`], for cases that will be run
+    pub(crate) descriptions: std::collections::BTreeMap<String, String>,
+    /// Captured `tracing` events of failed cases
+    pub(crate) logs: std::collections::BTreeMap<String, String>,
     pub(crate) elapsed_s: super::Elapsed,
+    /// `--fail-fast` stopped the run, so some cases may not have run
+    pub(crate) aborted: bool,
 
     pub(crate) num_run: usize,
     /// Number of tests and benchmarks that were filtered out (either by the
@@ -20,11 +32,38 @@ pub(crate) struct Summary {
     pub(crate) num_failed: usize,
     /// Number of ignored tests and benchmarks.
     pub(crate) num_ignored: usize,
+    /// Number of tests and benchmarks that could not be run.
+    pub(crate) num_errored: usize,
+    /// Number of tests and benchmarks that skipped themselves at runtime.
+    pub(crate) num_skipped: usize,
+
+    /// Whether to end with [`RESULT_LINE_PREFIX`], for tools scraping the output
+    pub(crate) result_line: bool,
+    /// Counts for each top-level module, from `--subtotals`
+    pub(crate) subtotals: Option<std::collections::BTreeMap<String, Subtotal>>,
+
+    /// Cases that failed in the `--baseline` run
+    pub(crate) baseline: Option<std::collections::BTreeSet<String>>,
+    /// Cases that failed in the `--baseline` run but passed now
+    pub(crate) fixed: std::collections::BTreeSet<String>,
+}
+
+/// How the cases of a top-level module turned out
+#[derive(Default, Clone, Debug)]
+pub(crate) struct Subtotal {
+    num_passed: usize,
+    num_failed: usize,
+    num_ignored: usize,
+    num_errored: usize,
+    num_skipped: usize,
 }
 
+/// Start of a final line whose format is kept stable, unlike the human-readable summary
+pub(crate) const RESULT_LINE_PREFIX: &str = "::libtest2:result";
+
 impl Summary {
     pub(crate) fn has_failed(&self) -> bool {
-        0 < self.num_failed
+        0 < self.num_failed || 0 < self.num_errored
     }
 
     pub(crate) fn write_start(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
@@ -34,8 +73,14 @@ impl Summary {
             .map(|s| format!(" (shuffle seed: {s})"))
             .unwrap_or_default();
 
+        let suite = self
+            .suite
+            .as_deref()
+            .map(|s| format!("{s}: "))
+            .unwrap_or_default();
+
         writeln!(writer)?;
-        writeln!(writer, "running {} test{s}{seed}", self.num_run)?;
+        writeln!(writer, "{suite}running {} test{s}{seed}", self.num_run)?;
         Ok(())
     }
 
@@ -48,6 +93,17 @@ impl Summary {
         let num_passed = self.num_passed;
         let num_failed = self.num_failed;
         let num_ignored = self.num_ignored;
+        // Only shown when relevant to stay compatible with libtest's summary line
+        let num_skipped = if 0 < self.num_skipped {
+            format!(" {} skipped;", self.num_skipped)
+        } else {
+            String::new()
+        };
+        let num_errored = if 0 < self.num_errored {
+            format!(" {} errored;", self.num_errored)
+        } else {
+            String::new()
+        };
         let num_filtered_out = self.num_filtered_out;
         let elapsed_s = self.elapsed_s;
 
@@ -58,71 +114,228 @@ impl Summary {
 
             // Print messages of all tests
             for (name, msg) in &self.failures {
-                if let Some(msg) = msg {
-                    writeln!(writer, "---- {} ----", name)?;
+                let header = match self.descriptions.get(name) {
+                    Some(description) => format!("{name}: {description}"),
+                    None => name.clone(),
+                };
+                if let Some(diff) = self.diffs.get(name) {
+                    writeln!(writer, "---- {} ----", header)?;
+                    write_diff(writer, diff)?;
+                    writeln!(writer)?;
+                } else if let Some(msg) = msg {
+                    writeln!(writer, "---- {} ----", header)?;
                     writeln!(writer, "{}", msg)?;
                     writeln!(writer)?;
                 }
+                if let Some(logs) = self.logs.get(name) {
+                    writeln!(writer, "---- {} logs ----", name)?;
+                    write!(writer, "{}", logs)?;
+                    writeln!(writer)?;
+                }
             }
 
             // Print summary list of failed tests
             writeln!(writer)?;
             writeln!(writer, "failures:")?;
             for name in self.failures.keys() {
-                writeln!(writer, "    {}", name)?;
+                let is_new = self
+                    .baseline
+                    .as_ref()
+                    .is_some_and(|baseline| !baseline.contains(name));
+                if is_new {
+                    writeln!(writer, "    {} (NEW FAILURE)", name)?;
+                } else {
+                    writeln!(writer, "    {}", name)?;
+                }
+            }
+        }
+        let num_not_run = self.num_run.saturating_sub(
+            self.num_passed
+                + self.num_failed
+                + self.num_ignored
+                + self.num_errored
+                + self.num_skipped,
+        );
+        if self.aborted && 0 < num_not_run {
+            let s = if num_not_run == 1 { "" } else { "s" };
+            writeln!(writer)?;
+            writeln!(
+                writer,
+                "aborted after first failure; {num_not_run} test{s} not run"
+            )?;
+        }
+        if let Some(subtotals) = &self.subtotals {
+            writeln!(writer)?;
+            writeln!(writer, "subtotals:")?;
+            for (module, subtotal) in subtotals {
+                let num_skipped = if 0 < subtotal.num_skipped {
+                    format!("; {} skipped", subtotal.num_skipped)
+                } else {
+                    String::new()
+                };
+                let num_errored = if 0 < subtotal.num_errored {
+                    format!("; {} errored", subtotal.num_errored)
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    writer,
+                    "    {module}: {} passed; {} failed; {} ignored{num_skipped}{num_errored}",
+                    subtotal.num_passed, subtotal.num_failed, subtotal.num_ignored
+                )?;
+            }
+        }
+        if !self.fixed.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "fixed:")?;
+            for name in &self.fixed {
+                writeln!(writer, "    {} (FIXED)", name)?;
             }
         }
         writeln!(writer)?;
         writeln!(
                     writer,
-                    "test result: {}{summary}{}. {num_passed} passed; {num_failed} failed; {num_ignored} ignored; \
+                    "test result: {}{summary}{}. {num_passed} passed; {num_failed} failed; {num_ignored} ignored;{num_skipped}{num_errored} \
                         {num_filtered_out} filtered out; finished in {elapsed_s}",
                     summary_style.render(),
                     summary_style.render_reset()
                 )?;
         writeln!(writer)?;
+        if self.result_line {
+            writeln!(
+                writer,
+                "{RESULT_LINE_PREFIX} passed={num_passed} failed={num_failed} ignored={num_ignored} \
+                    skipped={} errored={} filtered_out={num_filtered_out}",
+                self.num_skipped, self.num_errored
+            )?;
+        }
 
         Ok(())
     }
 }
 
 impl super::Notifier for Summary {
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        if let (Some(subtotals), Event::CaseComplete { name, status, .. }) =
+            (&mut self.subtotals, event)
+        {
+            // Cases outside of a module are grouped like with `--format=junit`
+            let module = name
+                .split_once("::")
+                .map(|(module, _)| module)
+                .unwrap_or("crate");
+            let subtotal = subtotals.entry(module.to_owned()).or_default();
+            match status {
+                Some(RunStatus::Ignored) => subtotal.num_ignored += 1,
+                Some(RunStatus::Skipped) => subtotal.num_skipped += 1,
+                Some(RunStatus::Failed) => subtotal.num_failed += 1,
+                Some(RunStatus::Errored) => subtotal.num_errored += 1,
+                None => subtotal.num_passed += 1,
+            }
+        }
         match event {
             Event::DiscoverStart => {}
-            Event::DiscoverCase { run, .. } => {
-                if run {
+            Event::DiscoverCase {
+                name,
+                run,
+                description,
+                ..
+            } => {
+                if *run {
                     self.num_run += 1;
+                    if let Some(description) = description {
+                        self.descriptions.insert(name.clone(), description.clone());
+                    }
                 } else {
                     self.num_filtered_out += 1;
                 }
             }
             Event::DiscoverComplete { seed, .. } => {
-                self.seed = seed;
+                self.seed = *seed;
+            }
+            Event::SuiteStart { suite } => {
+                self.suite = suite.clone();
             }
-            Event::SuiteStart => {}
             Event::CaseStart { .. } => {}
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete { .. } => {}
             Event::CaseComplete {
                 name,
                 status,
                 message,
+                diff,
+                location,
+                failure_location,
+                logs,
                 ..
             } => match status {
                 Some(RunStatus::Ignored) => {
                     self.num_ignored += 1;
                 }
-                Some(RunStatus::Failed) => {
-                    self.num_failed += 1;
-                    self.failures.insert(name, message);
+                Some(RunStatus::Skipped) => {
+                    self.num_skipped += 1;
+                }
+                Some(status @ (RunStatus::Failed | RunStatus::Errored)) => {
+                    if *status == RunStatus::Errored {
+                        self.num_errored += 1;
+                    } else {
+                        self.num_failed += 1;
+                    }
+                    if let Some(diff) = diff {
+                        self.diffs.insert(name.clone(), diff.clone());
+                    }
+                    if let Some(logs) = logs {
+                        self.logs.insert(name.clone(), logs.clone());
+                    }
+                    // Let editors jump to the failing case
+                    let location = failure_location.as_ref().or(location.as_ref());
+                    let message = match (location, message) {
+                        (Some(location), Some(message)) => Some(format!("{location}: {message}")),
+                        (Some(location), None) => Some(location.clone()),
+                        (None, message) => message.clone(),
+                    };
+                    self.failures.insert(name.clone(), message);
                 }
                 None => {
                     self.num_passed += 1;
+                    if self
+                        .baseline
+                        .as_ref()
+                        .is_some_and(|baseline| baseline.contains(name))
+                    {
+                        self.fixed.insert(name.clone());
+                    }
                 }
             },
-            Event::SuiteComplete { elapsed_s, .. } => {
-                self.elapsed_s = elapsed_s;
+            Event::SuiteComplete { elapsed_s, aborted } => {
+                self.elapsed_s = *elapsed_s;
+                self.aborted = *aborted;
             }
         }
         Ok(())
     }
 }
+
+fn write_diff(writer: &mut dyn std::io::Write, diff: &Diff) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "{}--- expected{}",
+        DIFF_EXPECTED.render(),
+        DIFF_EXPECTED.render_reset()
+    )?;
+    writeln!(
+        writer,
+        "{}+++ actual{}",
+        DIFF_ACTUAL.render(),
+        DIFF_ACTUAL.render_reset()
+    )?;
+    for line in diff.lines() {
+        let style = match line {
+            DiffLine::Context(_) => anstyle::Style::new(),
+            DiffLine::Expected(_) => DIFF_EXPECTED,
+            DiffLine::Actual(_) => DIFF_ACTUAL,
+        };
+        writeln!(writer, "{}{line}{}", style.render(), style.render_reset())?;
+    }
+    Ok(())
+}
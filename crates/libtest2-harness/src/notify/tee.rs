@@ -0,0 +1,27 @@
+use super::Event;
+
+/// Forward events to multiple notifiers
+pub(crate) struct TeeNotifier {
+    notifiers: Vec<Box<dyn super::Notifier>>,
+}
+
+impl TeeNotifier {
+    pub(crate) fn new(notifiers: Vec<Box<dyn super::Notifier>>) -> Self {
+        Self { notifiers }
+    }
+}
+
+impl super::Notifier for TeeNotifier {
+    fn threaded(&mut self, yes: bool) {
+        for notifier in &mut self.notifiers {
+            notifier.threaded(yes);
+        }
+    }
+
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        for notifier in &mut self.notifiers {
+            notifier.notify(event)?;
+        }
+        Ok(())
+    }
+}
@@ -1,39 +1,66 @@
 use super::Event;
 use super::RunStatus;
+use super::ERRORED;
 use super::FAILED;
 use super::IGNORED;
 use super::OK;
+use super::SKIPPED;
 
 #[derive(Debug)]
 pub(crate) struct TerseListNotifier<W> {
     writer: W,
     tests: usize,
+    plain: bool,
 }
 
 impl<W: std::io::Write> TerseListNotifier<W> {
     pub(crate) fn new(writer: W) -> Self {
-        Self { writer, tests: 0 }
+        Self {
+            writer,
+            tests: 0,
+            plain: false,
+        }
+    }
+
+    /// Only write the names, for `--list-format=plain`
+    pub(crate) fn plain(mut self, yes: bool) -> Self {
+        self.plain = yes;
+        self
     }
 }
 
 impl<W: std::io::Write> super::Notifier for TerseListNotifier<W> {
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
         match event {
             Event::DiscoverStart => {}
-            Event::DiscoverCase { name, mode, run } => {
-                if run {
-                    let mode = mode.as_str();
-                    writeln!(self.writer, "{name}: {mode}")?;
+            Event::DiscoverCase {
+                name,
+                mode,
+                run,
+                ignored,
+                ..
+            } => {
+                if *run {
+                    if self.plain {
+                        writeln!(self.writer, "{name}")?;
+                    } else {
+                        let mode = mode.as_str();
+                        let ignored = if *ignored { " (ignored)" } else { "" };
+                        writeln!(self.writer, "{name}: {mode}{ignored}")?;
+                    }
                     self.tests += 1;
                 }
             }
+            Event::DiscoverComplete { .. } if self.plain => {}
             Event::DiscoverComplete { .. } => {
                 writeln!(self.writer)?;
                 writeln!(self.writer, "{} tests", self.tests)?;
                 writeln!(self.writer)?;
             }
-            Event::SuiteStart => {}
+            Event::SuiteStart { .. } => {}
             Event::CaseStart { .. } => {}
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete { .. } => {}
             Event::CaseComplete { .. } => {}
             Event::SuiteComplete { .. } => {}
         }
@@ -41,10 +68,16 @@ impl<W: std::io::Write> super::Notifier for TerseListNotifier<W> {
     }
 }
 
+/// Columns to wrap at when not writing to a terminal
+const DEFAULT_WIDTH: usize = 100;
+
 #[derive(Debug)]
 pub(crate) struct TerseRunNotifier<W> {
     writer: W,
     summary: super::Summary,
+    completed: usize,
+    count: bool,
+    width: usize,
 }
 
 impl<W: std::io::Write> TerseRunNotifier<W> {
@@ -52,28 +85,87 @@ impl<W: std::io::Write> TerseRunNotifier<W> {
         Self {
             writer,
             summary: Default::default(),
+            completed: 0,
+            count: false,
+            width: DEFAULT_WIDTH,
         }
     }
+
+    /// Wrap lines at the width of the terminal, when writing to one
+    pub(crate) fn width(mut self, columns: Option<usize>) -> Self {
+        self.width = columns.unwrap_or(DEFAULT_WIDTH);
+        self
+    }
+
+    /// End each wrapped line with how many cases have completed, like ` 88/1000`
+    ///
+    /// This is for watching progress in a terminal, without cluttering logs.
+    pub(crate) fn count(mut self, yes: bool) -> Self {
+        self.count = yes;
+        self
+    }
+
+    /// End with a machine-readable result line, see [`super::Summary::result_line`]
+    pub(crate) fn result_line(mut self, yes: bool) -> Self {
+        self.summary.result_line = yes;
+        self
+    }
+
+    /// Show results per module, see [`super::Summary::subtotals`]
+    pub(crate) fn subtotals(mut self, yes: bool) -> Self {
+        self.summary.subtotals = yes.then(Default::default);
+        self
+    }
+
+    /// Mark changes since `--baseline`, see [`super::Summary::baseline`]
+    pub(crate) fn baseline(mut self, baseline: Option<std::collections::BTreeSet<String>>) -> Self {
+        self.summary.baseline = baseline;
+        self
+    }
+}
+
+impl<W> TerseRunNotifier<W> {
+    /// Leaves room for the count, so a wrapped line still fits
+    fn cases_per_line(&self) -> usize {
+        let count = if self.count {
+            let digits = self.summary.num_run.to_string().len();
+            " /".len() + 2 * digits
+        } else {
+            0
+        };
+        self.width.saturating_sub(count).max(1)
+    }
 }
 
 impl<W: std::io::Write> super::Notifier for TerseRunNotifier<W> {
-    fn notify(&mut self, event: Event) -> std::io::Result<()> {
-        self.summary.notify(event.clone())?;
+    fn notify(&mut self, event: &Event) -> std::io::Result<()> {
+        self.summary.notify(event)?;
         match event {
             Event::DiscoverStart => {}
             Event::DiscoverCase { .. } => {}
             Event::DiscoverComplete { .. } => {}
-            Event::SuiteStart => {
+            Event::SuiteStart { .. } => {
                 self.summary.write_start(&mut self.writer)?;
             }
             Event::CaseStart { .. } => {}
+            Event::CaseOutput { .. } => {}
+            Event::BenchComplete { .. } => {}
             Event::CaseComplete { status, .. } => {
                 let (c, style) = match status {
                     Some(RunStatus::Ignored) => ('i', IGNORED),
+                    Some(RunStatus::Skipped) => ('s', SKIPPED),
                     Some(RunStatus::Failed) => ('F', FAILED),
+                    Some(RunStatus::Errored) => ('E', ERRORED),
                     None => ('.', OK),
                 };
                 write!(self.writer, "{}{c}{}", style.render(), style.render_reset())?;
+                self.completed += 1;
+                if self.completed % self.cases_per_line() == 0 {
+                    if self.count {
+                        write!(self.writer, " {}/{}", self.completed, self.summary.num_run)?;
+                    }
+                    writeln!(self.writer)?;
+                }
                 self.writer.flush()?;
             }
             Event::SuiteComplete { .. } => {
@@ -83,3 +175,53 @@ impl<W: std::io::Write> super::Notifier for TerseRunNotifier<W> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::Notifier as _;
+    use super::*;
+
+    #[test]
+    fn count_wrapped_lines() {
+        let mut notifier = TerseRunNotifier::new(Vec::new())
+            .count(true)
+            .width(Some(42));
+        let names = (0..36 + 2).map(|i| format!("case{i}")).collect::<Vec<_>>();
+        for name in &names {
+            notifier
+                .notify(&Event::DiscoverCase {
+                    name: name.clone(),
+                    mode: Default::default(),
+                    run: true,
+                    ignored: false,
+                    description: None,
+                })
+                .unwrap();
+        }
+        notifier.writer.clear();
+        for name in &names {
+            notifier
+                .notify(&Event::CaseComplete {
+                    name: name.clone(),
+                    mode: Default::default(),
+                    status: None,
+                    message: None,
+                    elapsed_s: None,
+                    timings: None,
+                    metadata: Default::default(),
+                    logs: None,
+                    diff: None,
+                    location: None,
+                    failure_location: None,
+                    #[cfg(feature = "json")]
+                    at_s: None,
+                })
+                .unwrap();
+        }
+
+        let output = String::from_utf8(notifier.writer).unwrap();
+        let output = anstream::adapter::strip_str(&output).to_string();
+        let dots = ".".repeat(36);
+        assert_eq!(output, format!("{dots} 36/38\n.."));
+    }
+}
@@ -0,0 +1,65 @@
+use libtest_lexarg::Partition;
+use libtest_lexarg::PartitionStrategy;
+
+use crate::hash::fnv1a;
+
+/// Names from `names` that belong to `partition`
+///
+/// `names` must be the same on every machine for the shards to line up, regardless of `--order`
+/// or `--shuffle`.
+pub(crate) fn select(
+    partition: Partition,
+    mut names: Vec<String>,
+) -> std::collections::HashSet<String> {
+    let shard = partition.index - 1;
+    match partition.strategy {
+        PartitionStrategy::Count => {
+            names.sort_unstable();
+            let start = shard * names.len() / partition.total;
+            let end = (shard + 1) * names.len() / partition.total;
+            names.drain(start..end).collect()
+        }
+        PartitionStrategy::Hash => names
+            .into_iter()
+            .filter(|name| fnv1a(name) % partition.total as u64 == shard as u64)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shards(strategy: PartitionStrategy, total: usize, names: &[&str]) -> Vec<Vec<String>> {
+        (1..=total)
+            .map(|index| {
+                let partition = Partition {
+                    strategy,
+                    index,
+                    total,
+                };
+                let names = names.iter().map(|n| (*n).to_owned()).collect();
+                let mut selected = select(partition, names).into_iter().collect::<Vec<_>>();
+                selected.sort_unstable();
+                selected
+            })
+            .collect()
+    }
+
+    #[test]
+    fn count_is_contiguous() {
+        let actual = shards(PartitionStrategy::Count, 3, &["e", "d", "c", "b", "a"]);
+        assert_eq!(actual, [vec!["a"], vec!["b", "c"], vec!["d", "e"]]);
+    }
+
+    #[test]
+    fn hash_covers_every_name_once() {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut actual = shards(PartitionStrategy::Hash, 3, &names)
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        actual.sort_unstable();
+        assert_eq!(actual, names);
+    }
+}
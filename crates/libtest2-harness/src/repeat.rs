@@ -0,0 +1,70 @@
+use crate::*;
+
+/// Run each of `cases` `repeat` times, keeping the runs of a case together
+///
+/// Each run is a case of its own, named `name #N`, so they are scheduled and reported separately.
+pub(crate) fn repeat(
+    cases: Vec<Box<dyn Case>>,
+    repeat: Option<std::num::NonZeroUsize>,
+) -> Vec<Box<dyn Case>> {
+    let Some(repeat) = repeat else {
+        return cases;
+    };
+    cases
+        .into_iter()
+        .flat_map(|case| {
+            let case = std::sync::Arc::<dyn Case>::from(case);
+            (1..=repeat.get()).map(move |repetition| {
+                Box::new(Repetition {
+                    name: format!("{} #{repetition}", case.name()),
+                    case: case.clone(),
+                    repetition,
+                }) as Box<dyn Case>
+            })
+        })
+        .collect()
+}
+
+struct Repetition {
+    name: String,
+    case: std::sync::Arc<dyn Case>,
+    repetition: usize,
+}
+
+impl Case for Repetition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn kind(&self) -> TestKind {
+        self.case.kind()
+    }
+    fn mode(&self) -> RunMode {
+        self.case.mode()
+    }
+    fn source(&self) -> Option<&Source> {
+        self.case.source()
+    }
+    fn is_ignored(&self) -> Option<&str> {
+        self.case.is_ignored()
+    }
+    fn tags(&self) -> &[&str] {
+        self.case.tags()
+    }
+    fn describe(&self) -> Option<&str> {
+        self.case.describe()
+    }
+    fn timeout(&self) -> Option<std::time::Duration> {
+        self.case.timeout()
+    }
+    fn exclusive(&self, state: &State) -> bool {
+        self.case.exclusive(state)
+    }
+    fn main_thread(&self) -> bool {
+        self.case.main_thread()
+    }
+
+    fn run(&self, state: &State) -> Result<(), RunError> {
+        state.set_repetition(self.repetition);
+        self.case.run(state)
+    }
+}
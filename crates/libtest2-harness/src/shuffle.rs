@@ -1,11 +1,15 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::Hasher;
+//! Shuffle tests so that `--shuffle-seed` reproduces an order on any platform and Rust version
+//!
+//! The order depends on the seed and the names of the tests being shuffled, hashed with FNV-1a.
+//! It is a Fisher-Yates shuffle driven by SplitMix64, neither of which depend on `std`'s hashing.
+
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::Case;
 
-pub fn get_shuffle_seed(opts: &libtest_lexarg::TestOpts) -> Option<u64> {
-    opts.shuffle_seed.or_else(|| {
+/// `default` is used when `--shuffle-seed` isn't passed
+pub fn get_shuffle_seed(opts: &libtest_lexarg::TestOpts, default: Option<u64>) -> Option<u64> {
+    opts.shuffle_seed.or(default).or_else(|| {
         opts.shuffle.then(|| {
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -17,7 +21,7 @@ pub fn get_shuffle_seed(opts: &libtest_lexarg::TestOpts) -> Option<u64> {
 
 pub fn shuffle_tests(shuffle_seed: u64, tests: &mut [Box<dyn Case>]) {
     let test_names: Vec<&str> = tests.iter().map(|test| test.name()).collect();
-    let test_names_hash = calculate_hash(&test_names);
+    let test_names_hash = crate::hash::fnv1a(&test_names.join("\0"));
     let mut rng = Rng::new(shuffle_seed, test_names_hash);
     shuffle(&mut rng, tests);
 }
@@ -35,14 +39,16 @@ fn shuffle<T>(rng: &mut Rng, slice: &mut [T]) {
     }
 }
 
+/// SplitMix64, so a seed gives the same order regardless of the Rust version
 struct Rng {
     state: u64,
-    extra: u64,
 }
 
 impl Rng {
     fn new(seed: u64, extra: u64) -> Self {
-        Self { state: seed, extra }
+        Self {
+            state: seed ^ extra,
+        }
     }
 
     fn rand_range(&mut self, range: core::ops::Range<u64>) -> u64 {
@@ -50,14 +56,39 @@ impl Rng {
     }
 
     fn rand_u64(&mut self) -> u64 {
-        self.state = calculate_hash(&(self.state, self.extra));
-        self.state
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
     }
 }
 
-// `calculate_hash` is from `core/src/hash/mod.rs`.
-fn calculate_hash<T: core::hash::Hash>(t: &T) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_splitmix64() {
+        // From the reference implementation
+        let mut rng = Rng::new(1234567, 0);
+        let actual = (0..3).map(|_| rng.rand_u64()).collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            [
+                6457827717110365317,
+                3203168211198807973,
+                9817491932198370423
+            ]
+        );
+    }
+
+    #[test]
+    fn order_is_stable() {
+        let mut names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let hash = crate::hash::fnv1a(&names.join("\0"));
+        shuffle(&mut Rng::new(1, hash), &mut names);
+        // Changing this breaks reproducing orders from earlier runs
+        assert_eq!(names, ["e", "g", "f", "b", "d", "a", "c", "h"]);
+    }
 }
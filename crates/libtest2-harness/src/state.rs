@@ -4,9 +4,22 @@ pub use crate::*;
 pub struct State {
     mode: notify::RunMode,
     run_ignored: bool,
+    nocapture: bool,
+    test_threads: usize,
+    timeout: Option<std::time::Duration>,
+    iterations: usize,
+    repetition: std::sync::atomic::AtomicUsize,
+    deadline: Option<std::time::Instant>,
+    metadata: std::sync::Mutex<std::collections::BTreeMap<String, String>>,
+    bench_samples: std::sync::Mutex<Vec<u64>>,
+    logs: std::sync::Mutex<String>,
 }
 
 impl State {
+    /// Ignore this case unless ignored tests are requested
+    ///
+    /// As this is decided while running the case, `--ignored` can't tell which cases would ignore
+    /// themselves and only runs cases that are ignored through [`Case::is_ignored`].
     pub fn ignore(&self) -> Result<(), RunError> {
         if self.run_ignored {
             Ok(())
@@ -15,6 +28,7 @@ impl State {
         }
     }
 
+    /// Ignore this case unless ignored tests are requested, see [`State::ignore`]
     pub fn ignore_for(&self, reason: impl std::fmt::Display) -> Result<(), RunError> {
         if self.run_ignored {
             Ok(())
@@ -23,9 +37,168 @@ impl State {
         }
     }
 
+    /// Skip this case because it cannot run in the current environment
+    ///
+    /// Unlike [`State::ignore_for`], this applies even when ignored tests are requested.
+    pub fn skip(&self, reason: impl std::fmt::Display) -> Result<(), RunError> {
+        Err(RunError::skip(reason.to_string()))
+    }
+
+    /// Fail this case with `message`
+    ///
+    /// Like [`RunError::at`], this reports where it was called from.
+    #[track_caller]
+    pub fn fail(&self, message: impl std::fmt::Display) -> Result<(), RunError> {
+        let location = std::panic::Location::caller();
+        Err(RunError::fail(message).at(location.file(), location.line() as usize))
+    }
+
+    /// Fail this case unless `condition` holds, see [`State::fail`]
+    ///
+    /// `message` is only called on failure, so it may be costly to build.
+    #[track_caller]
+    pub fn assert<M: std::fmt::Display>(
+        &self,
+        condition: bool,
+        message: impl FnOnce() -> M,
+    ) -> Result<(), RunError> {
+        if condition {
+            Ok(())
+        } else {
+            self.fail(message())
+        }
+    }
+
+    /// Attach metadata to the result of this case
+    ///
+    /// This does not affect the outcome but is reported to machine-readable output, like
+    /// `--format=json`.  Recording the same `key` again overwrites the earlier value.
+    pub fn record(&self, key: impl Into<String>, value: impl std::fmt::Display) {
+        self.metadata
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.into(), value.to_string());
+    }
+
+    /// Report how long a benchmark took, in nanoseconds per iteration for each sample
+    ///
+    /// Like [`State::record`], this is lost when the case is run with `--run-in-subprocess`.
+    pub fn record_bench(&self, samples: impl IntoIterator<Item = u64>) {
+        self.bench_samples
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(samples);
+    }
+
+    /// Set the environment variable `key` to `value` while running `f`, restoring it after
+    ///
+    /// Cases changing variables this way don't race each other, but they still race cases that
+    /// read them, so return [`State::exclusive_env`] from [`Case::exclusive`] for such cases.
+    /// Calls may be nested within `f`.
+    pub fn scoped_env<R>(
+        &self,
+        key: impl AsRef<std::ffi::OsStr>,
+        value: impl AsRef<std::ffi::OsStr>,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        thread_local! {
+            /// Whether this thread holds `ENV_LOCK`, so nested calls don't wait on themselves
+            static HOLDS_LOCK: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+        }
+
+        /// Release `ENV_LOCK` if this is the outermost call
+        struct Lock(Option<std::sync::MutexGuard<'static, ()>>);
+
+        impl Drop for Lock {
+            fn drop(&mut self) {
+                if self.0.is_some() {
+                    HOLDS_LOCK.with(|held| held.set(false));
+                }
+            }
+        }
+
+        /// Restore the variable, even if `f` panics
+        struct Restore<'k> {
+            key: &'k std::ffi::OsStr,
+            previous: Option<std::ffi::OsString>,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                match self.previous.take() {
+                    Some(previous) => std::env::set_var(self.key, previous),
+                    None => std::env::remove_var(self.key),
+                }
+            }
+        }
+
+        let _lock = Lock(
+            (!HOLDS_LOCK.with(|held| held.replace(true)))
+                .then(|| ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())),
+        );
+        let key = key.as_ref();
+        let _restore = Restore {
+            key,
+            previous: std::env::var_os(key),
+        };
+        std::env::set_var(key, value);
+        f()
+    }
+
+    /// Mark a case that changes environment variables as [`Case::exclusive`]
+    ///
+    /// Return this from [`Case::exclusive`] for cases using [`State::scoped_env`], so no other case
+    /// reads the environment while it is changed.
+    pub fn exclusive_env(&self) -> bool {
+        true
+    }
+
+    /// Same as [`State::run_mode`]
     pub fn current_mode(&self) -> notify::RunMode {
         self.mode
     }
+
+    /// Whether tests or benchmarks are being run
+    pub fn run_mode(&self) -> notify::RunMode {
+        self.mode
+    }
+
+    /// Whether output of cases is shown as it happens, rather than captured
+    pub fn is_nocapture(&self) -> bool {
+        self.nocapture
+    }
+
+    /// The number of cases that may run at the same time
+    pub fn test_threads(&self) -> usize {
+        self.test_threads
+    }
+
+    /// Which run of this case this is, counting from 1, from `--repeat`
+    ///
+    /// This is `1` without `--repeat`.
+    pub fn repetition(&self) -> usize {
+        self.repetition.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// When this case should stop running, from `--timeout`
+    ///
+    /// Nothing stops the case at this time, see [`State::check_deadline`].
+    pub fn deadline(&self) -> Option<std::time::Instant> {
+        self.deadline
+    }
+
+    /// Fail if this case is past its [`State::deadline`]
+    ///
+    /// Long-running cases can call this periodically to stop early.
+    pub fn check_deadline(&self) -> Result<(), RunError> {
+        match (self.deadline, self.timeout) {
+            (Some(deadline), Some(timeout)) if deadline <= std::time::Instant::now() => Err(
+                RunError::fail(format!("exceeded timeout of {}s", timeout.as_secs_f64())),
+            ),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl State {
@@ -33,13 +206,87 @@ impl State {
         Self {
             mode: Default::default(),
             run_ignored: false,
+            nocapture: false,
+            test_threads: 1,
+            timeout: None,
+            iterations: 1,
+            repetition: std::sync::atomic::AtomicUsize::new(1),
+            deadline: None,
+            metadata: Default::default(),
+            bench_samples: Default::default(),
+            logs: Default::default(),
         }
     }
 
+    /// Fresh copy of the run-wide state for `case`, starting now
+    ///
+    /// [`Case::timeout`] takes precedence over `--timeout`.
+    pub(crate) fn for_case(&self, case: &dyn Case) -> Self {
+        let timeout = case.timeout().or(self.timeout);
+        Self {
+            mode: self.mode,
+            run_ignored: self.run_ignored,
+            nocapture: self.nocapture,
+            test_threads: self.test_threads,
+            timeout,
+            iterations: self.iterations,
+            repetition: std::sync::atomic::AtomicUsize::new(1),
+            deadline: timeout.and_then(|t| std::time::Instant::now().checked_add(t)),
+            metadata: Default::default(),
+            bench_samples: Default::default(),
+            logs: Default::default(),
+        }
+    }
+
+    pub(crate) fn take_metadata(&self) -> std::collections::BTreeMap<String, String> {
+        std::mem::take(&mut *self.metadata.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub(crate) fn take_bench_samples(&self) -> Vec<u64> {
+        std::mem::take(&mut *self.bench_samples.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub(crate) fn set_logs(&self, logs: String) {
+        *self.logs.lock().unwrap_or_else(|e| e.into_inner()) = logs;
+    }
+
+    pub(crate) fn take_logs(&self) -> String {
+        std::mem::take(&mut *self.logs.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
     pub(crate) fn set_mode(&mut self, mode: notify::RunMode) {
         self.mode = mode;
     }
 
+    pub(crate) fn set_nocapture(&mut self, yes: bool) {
+        self.nocapture = yes;
+    }
+
+    pub(crate) fn set_test_threads(&mut self, threads: usize) {
+        self.test_threads = threads;
+    }
+
+    pub(crate) fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout
+    }
+
+    pub(crate) fn set_timeout(&mut self, timeout: Option<std::time::Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub(crate) fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub(crate) fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations;
+    }
+
+    pub(crate) fn set_repetition(&self, repetition: usize) {
+        self.repetition
+            .store(repetition, std::sync::atomic::Ordering::Relaxed);
+    }
+
     pub(crate) fn set_run_ignored(&mut self, yes: bool) {
         self.run_ignored = yes;
     }
@@ -0,0 +1,277 @@
+//! Run each case in a child process so an abort only takes down that case
+//!
+//! The test binary is re-run with [`CASE_ENV`] set to the name of the case.  The child runs just
+//! that case and reports the outcome on stdout, after anything the case itself printed, as
+//! `<marker> <status>[ <location>]` followed by the message.  Logs captured with the `tracing`
+//! feature come right before the outcome, after a marker of their own.
+//!
+//! With `--nocapture`, what the case writes is passed to the parent line by line as it runs, see
+//! [`notify::Event::CaseOutput`].
+
+use crate::*;
+
+/// Name of the case a child process should run
+pub(crate) const CASE_ENV: &str = "__LIBTEST2_SUBPROCESS_CASE";
+
+/// Separates the case's own output from the outcome
+const OUTCOME_MARKER: &str = "__LIBTEST2_OUTCOME__";
+
+/// Separates the case's own output from its captured logs
+const LOGS_MARKER: &str = "__LIBTEST2_LOGS__";
+
+/// Options the child needs to run a case the same way as the parent, see [`run_case`]
+pub(crate) fn child_args(opts: &libtest_lexarg::TestOpts) -> Vec<std::ffi::OsString> {
+    let mut args = vec![std::ffi::OsString::from("-Zunstable-options")];
+    if opts.bench_benchmarks {
+        args.push("--bench".into());
+    }
+    match opts.run_ignored {
+        libtest_lexarg::RunIgnored::Yes => args.push("--include-ignored".into()),
+        libtest_lexarg::RunIgnored::Only => args.push("--ignored".into()),
+        libtest_lexarg::RunIgnored::No => {}
+    }
+    // The parent runs each iteration in a child of its own, this is only for `State`
+    if let Some(iterations) = opts.iterations {
+        args.push("--iterations".into());
+        args.push(iterations.to_string().into());
+    }
+    if let Some(repeat) = opts.repeat {
+        args.push("--repeat".into());
+        args.push(repeat.to_string().into());
+    }
+    args
+}
+
+/// Run `case` in a child process, passing it `args` from [`child_args`]
+///
+/// Each line the case writes is passed to `on_line`, when given, as soon as it is read.
+///
+/// Metadata recorded through [`State::record`] is not reported back to the parent.
+pub(crate) fn run_case(
+    case: &dyn Case,
+    state: &State,
+    args: &[std::ffi::OsString],
+    on_line: Option<&mut dyn FnMut(notify::OutputStream, String)>,
+) -> RunResult {
+    let exe = std::env::current_exe().map_err(|e| {
+        RunError::fail(format!(
+            "could not find test binary to run `{}`: {e}",
+            case.name()
+        ))
+    })?;
+    let mut cmd = std::process::Command::new(exe);
+    cmd.args(args);
+    // Per case, from `Case::timeout`
+    if let Some(timeout) = state.timeout() {
+        cmd.arg("--timeout").arg(timeout.as_secs_f64().to_string());
+    }
+    cmd.env(CASE_ENV, case.name())
+        .env_remove("LIBTEST2_ARGS")
+        .stdin(std::process::Stdio::null());
+    let output = match on_line {
+        Some(on_line) => stream_output(&mut cmd, on_line),
+        None => cmd.output(),
+    }
+    .map_err(|e| RunError::fail(format!("could not run `{}`: {e}", case.name())))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (stdout, outcome) = match stdout.rfind(OUTCOME_MARKER) {
+        Some(i) => (&stdout[..i], Some(&stdout[i + OUTCOME_MARKER.len()..])),
+        None => (&stdout[..], None),
+    };
+    if let Some(i) = stdout.rfind(LOGS_MARKER) {
+        let logs = &stdout[i + LOGS_MARKER.len()..];
+        // Drop the newlines around the logs from `child_main`
+        let logs = logs.strip_prefix('\n').unwrap_or(logs);
+        let logs = logs.strip_suffix('\n').unwrap_or(logs);
+        state.set_logs(logs.to_owned());
+    }
+    match outcome {
+        Some(outcome) if output.status.success() => parse_outcome(outcome),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            if stderr.is_empty() {
+                Err(RunError::fail(format!(
+                    "process exited with {}",
+                    output.status
+                )))
+            } else {
+                Err(RunError::fail(format!(
+                    "process exited with {}\n{stderr}",
+                    output.status
+                )))
+            }
+        }
+    }
+}
+
+/// Like [`std::process::Command::output`], passing each line written to `on_line` along the way
+///
+/// Lines are passed without their newline and stop at the outcome, or the logs when there are any.
+fn stream_output(
+    cmd: &mut std::process::Command,
+    on_line: &mut dyn FnMut(notify::OutputStream, String),
+) -> std::io::Result<std::process::Output> {
+    let mut child = cmd
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let (stdout, stderr) = std::thread::scope(|scope| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let stdout_tx = tx.clone();
+        let stdout =
+            scope.spawn(move || read_lines(stdout, notify::OutputStream::Stdout, &stdout_tx));
+        let stderr = scope.spawn(move || read_lines(stderr, notify::OutputStream::Stderr, &tx));
+        // Ends once both readers hang up
+        for (stream, line) in rx {
+            on_line(stream, line);
+        }
+        (
+            stdout.join().expect("reader doesn't panic"),
+            stderr.join().expect("reader doesn't panic"),
+        )
+    });
+    Ok(std::process::Output {
+        status: child.wait()?,
+        stdout: stdout?,
+        stderr: stderr?,
+    })
+}
+
+/// Send each line of `reader` to `tx`, returning everything that was read
+fn read_lines(
+    reader: impl std::io::Read,
+    stream: notify::OutputStream,
+    tx: &std::sync::mpsc::Sender<(notify::OutputStream, String)>,
+) -> std::io::Result<Vec<u8>> {
+    use std::io::BufRead as _;
+
+    let mut reader = std::io::BufReader::new(reader);
+    let mut all = Vec::new();
+    // `child_main` puts a newline of its own before the markers, so a line is only sent once the
+    // next one shows it isn't that newline
+    let mut pending: Option<String> = None;
+    let mut done = false;
+    loop {
+        let start = all.len();
+        if reader.read_until(b'\n', &mut all)? == 0 {
+            break;
+        }
+        if done {
+            continue;
+        }
+        let line = String::from_utf8_lossy(&all[start..]);
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        let line = line.strip_suffix('\r').unwrap_or(line).to_owned();
+        if stream == notify::OutputStream::Stderr {
+            let _ = tx.send((stream, line));
+            continue;
+        }
+        if line.starts_with(OUTCOME_MARKER) || line == LOGS_MARKER {
+            done = true;
+            // The line before the marker ends at that newline, so it's only a line of the case's
+            // when the case didn't end its output with a newline
+            pending = pending.filter(|pending| !pending.is_empty());
+        }
+        if let Some(pending) = pending.take() {
+            // If the receiver doesn't care, neither do we
+            let _ = tx.send((stream, pending));
+        }
+        if !done {
+            pending = Some(line);
+        }
+    }
+    if let Some(pending) = pending {
+        let _ = tx.send((stream, pending));
+    }
+    Ok(all)
+}
+
+/// Run the case named `name` and report the outcome to [`run_case`] in the parent
+pub(crate) fn child_main(name: &std::ffi::OsStr, cases: &[Box<dyn Case>], state: &State) -> ! {
+    let Some(case) = cases
+        .iter()
+        .find(|case| std::ffi::OsStr::new(case.name()) == name)
+    else {
+        eprintln!("error: no case named `{}`", name.to_string_lossy());
+        std::process::exit(1)
+    };
+
+    let state = state.for_case(case.as_ref());
+    let outcome = harness::run_case_outcome(case.as_ref(), &state);
+    let (status, message, location) = match &outcome {
+        Ok(()) => ("ok", None, None),
+        Err(err) => {
+            let status = match err.status() {
+                notify::RunStatus::Ignored => "ignored",
+                notify::RunStatus::Skipped => "skipped",
+                notify::RunStatus::Failed => "failed",
+                notify::RunStatus::Errored => "errored",
+            };
+            (status, err.message(), err.location())
+        }
+    };
+    let logs = state.take_logs();
+    if !logs.is_empty() {
+        println!();
+        print!("{LOGS_MARKER}\n{logs}");
+    }
+    println!();
+    match location {
+        Some(location) => println!("{OUTCOME_MARKER} {status} {location}"),
+        None => println!("{OUTCOME_MARKER} {status}"),
+    }
+    if let Some(message) = message {
+        print!("{message}");
+    }
+    std::process::exit(0)
+}
+
+fn parse_outcome(outcome: &str) -> RunResult {
+    let outcome = outcome.strip_prefix(' ').unwrap_or(outcome);
+    let (status, message) = outcome.split_once('\n').unwrap_or((outcome, ""));
+    let (status, location) = match status.split_once(' ') {
+        Some((status, location)) => (status, Some(location.to_owned())),
+        None => (status, None),
+    };
+    let err = match status {
+        "ok" => return Ok(()),
+        "ignored" if message.is_empty() => RunError::ignore(),
+        "ignored" => RunError::ignore_for(message.to_owned()),
+        "skipped" => RunError::skip(message.to_owned()),
+        "errored" => RunError::error(message),
+        _ => RunError::fail(message),
+    };
+    Err(err.with_location(location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_outcomes() {
+        assert!(parse_outcome(" ok\n").is_ok());
+
+        let err = parse_outcome(" ignored\n").unwrap_err();
+        assert_eq!(err.status(), notify::RunStatus::Ignored);
+        assert!(err.cause().is_none());
+
+        let err = parse_outcome(" skipped\nno network").unwrap_err();
+        assert_eq!(err.status(), notify::RunStatus::Skipped);
+        assert_eq!(err.cause().unwrap().to_string(), "no network");
+
+        let err = parse_outcome(" failed\nline 1\nline 2").unwrap_err();
+        assert_eq!(err.status(), notify::RunStatus::Failed);
+        assert_eq!(err.cause().unwrap().to_string(), "line 1\nline 2");
+        assert!(err.location().is_none());
+
+        let err = parse_outcome(" failed data/my cases.txt:3\nmismatch").unwrap_err();
+        assert_eq!(err.status(), notify::RunStatus::Failed);
+        assert_eq!(err.location(), Some("data/my cases.txt:3"));
+        assert_eq!(err.cause().unwrap().to_string(), "mismatch");
+    }
+}
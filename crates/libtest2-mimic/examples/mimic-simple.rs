@@ -1,4 +1,3 @@
-use libtest2_mimic::RunError;
 use libtest2_mimic::RunResult;
 use libtest2_mimic::State;
 use libtest2_mimic::Trial;
@@ -21,8 +20,8 @@ fn check_toph(_state: &State) -> RunResult {
 fn check_katara(_state: &State) -> RunResult {
     Ok(())
 }
-fn check_sokka(_state: &State) -> RunResult {
-    Err(RunError::fail("Sokka tripped and fell :("))
+fn check_sokka(state: &State) -> RunResult {
+    state.fail("Sokka tripped and fell :(")
 }
 fn long_computation(state: &State) -> RunResult {
     state.ignore_for("slow")?;
@@ -22,31 +22,141 @@
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "async")]
+pub use libtest2_harness::AsyncCase;
+pub use libtest2_harness::Case;
+pub use libtest2_harness::CaseOutcome;
+pub use libtest2_harness::Conclusion;
+pub use libtest2_harness::Event;
+#[cfg(feature = "async")]
+pub use libtest2_harness::Executor;
 pub use libtest2_harness::Harness;
+pub use libtest2_harness::OutputFormat;
 pub use libtest2_harness::RunError;
+#[cfg(feature = "async")]
+pub use libtest2_harness::RunFuture;
+pub use libtest2_harness::RunMode;
 pub use libtest2_harness::RunResult;
+pub use libtest2_harness::RunStatus;
 pub use libtest2_harness::State;
 pub use libtest2_harness::TestKind;
+pub use libtest2_harness::TestOpts;
 
-use libtest2_harness::Case;
 use libtest2_harness::Source;
 
 pub struct Trial {
     name: String,
+    mode: RunMode,
+    main_thread: bool,
+    exclusive: bool,
+    source: Option<Source>,
+    ignored: Option<String>,
+    tags: Vec<&'static str>,
+    description: Option<String>,
+    timeout: Option<std::time::Duration>,
     #[allow(clippy::type_complexity)]
     runner: Box<dyn Fn(&State) -> Result<(), RunError> + Send + Sync>,
 }
 
 impl Trial {
+    /// The [`Source`] is the location of the caller
+    #[track_caller]
     pub fn test(
         name: impl Into<String>,
         runner: impl Fn(&State) -> Result<(), RunError> + Send + Sync + 'static,
     ) -> Self {
         Self {
             name: name.into(),
+            mode: RunMode::Test,
+            main_thread: false,
+            exclusive: false,
+            source: Some(caller_source()),
+            ignored: None,
+            tags: Vec::new(),
+            description: None,
+            timeout: None,
             runner: Box::new(runner),
         }
     }
+
+    /// The [`Source`] is the location of the caller
+    #[track_caller]
+    pub fn bench(
+        name: impl Into<String>,
+        runner: impl Fn(&State) -> Result<(), RunError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mode: RunMode::Bench,
+            main_thread: false,
+            exclusive: false,
+            source: Some(caller_source()),
+            ignored: None,
+            tags: Vec::new(),
+            description: None,
+            timeout: None,
+            runner: Box::new(runner),
+        }
+    }
+
+    /// Ignore this case unless ignored tests are requested
+    ///
+    /// `reason` may be empty.
+    pub fn ignore(mut self, reason: impl Into<String>) -> Self {
+        self.ignored = Some(reason.into());
+        self
+    }
+
+    /// Label this case for `--include-tag` and `--exclude-tag`
+    ///
+    /// May be called multiple times to add more tags.
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// The scenario this case checks, see [`Case::describe`]
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// How long this case has to run, instead of `--timeout`
+    ///
+    /// See [`State::check_deadline`].
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Don't run other cases in parallel to this one
+    ///
+    /// This is for cases that change process-wide state other cases may read, like environment
+    /// variables, see [`State::scoped_env`].
+    pub fn with_exclusive(mut self, yes: bool) -> Self {
+        self.exclusive = yes;
+        self
+    }
+
+    /// Run on the thread that called [`Harness::main`], even when running tests in parallel
+    pub fn with_main_thread(mut self, yes: bool) -> Self {
+        self.main_thread = yes;
+        self
+    }
+}
+
+#[track_caller]
+fn caller_source() -> Source {
+    let location = std::panic::Location::caller();
+    let line = location.line() as usize;
+    let col = location.column() as usize;
+    Source::Rust {
+        source_file: std::path::PathBuf::from(location.file()),
+        start_line: line,
+        start_col: col,
+        end_line: line,
+        end_col: col,
+    }
 }
 
 impl Case for Trial {
@@ -56,11 +166,29 @@ impl Case for Trial {
     fn kind(&self) -> TestKind {
         Default::default()
     }
+    fn mode(&self) -> RunMode {
+        self.mode
+    }
     fn source(&self) -> Option<&Source> {
-        None
+        self.source.as_ref()
+    }
+    fn is_ignored(&self) -> Option<&str> {
+        self.ignored.as_deref()
+    }
+    fn tags(&self) -> &[&str] {
+        &self.tags
+    }
+    fn describe(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout
     }
     fn exclusive(&self, _: &State) -> bool {
-        false
+        self.exclusive
+    }
+    fn main_thread(&self) -> bool {
+        self.main_thread
     }
 
     fn run(&self, state: &State) -> Result<(), RunError> {
@@ -19,7 +19,7 @@ fn main() {
             let bin = crate::util::compile_test(&package_root);
             (bin, package_root)
         });
-    snapbox::cmd::Command::new(&BIN.0).current_dir(&BIN.1)
+    crate::util::test_bin(&BIN.0, &BIN.1)
 }
 
 fn check(args: &[&str], single: &str, parallel: &str) {
@@ -0,0 +1,137 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+use libtest2_mimic::RunError;
+use libtest2_mimic::RunFuture;
+use libtest2_mimic::State;
+
+struct ThreadExecutor;
+
+impl libtest2_mimic::Executor for ThreadExecutor {
+    fn block_on(&self, mut future: RunFuture<'_>) -> Result<(), RunError> {
+        struct Unpark(std::thread::Thread);
+        impl std::task::Wake for Unpark {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        let waker = std::sync::Arc::new(Unpark(std::thread::current())).into();
+        let mut cx = std::task::Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                std::task::Poll::Ready(result) => return result,
+                std::task::Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+}
+
+/// Pending once, to exercise the waker
+struct YieldNow(bool);
+
+impl std::future::Future for YieldNow {
+    type Output = ();
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
+struct Fetch {
+    name: &'static str,
+    status: u16,
+}
+
+impl libtest2_mimic::AsyncCase for Fetch {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(&self, _state: &State) -> Result<(), RunError> {
+        YieldNow(false).await;
+        match self.status {
+            200 => Ok(()),
+            status => Err(RunError::fail(format!("got status {status}"))),
+        }
+    }
+}
+
+fn main() {
+    libtest2_mimic::Harness::with_env()
+        .async_cases(
+            ThreadExecutor,
+            [
+                Fetch { name: "found", status: 200 },
+                Fetch { name: "missing", status: 404 },
+            ],
+        )
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn normal() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+test found   ... ok
+test missing ... FAILED
+
+failures:
+
+---- missing ----
+got status 404
+
+
+failures:
+    missing
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn parallel() {
+    test_cmd()
+        .args(["--test-threads", "2"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+...
+
+failures:
+
+---- missing ----
+got status 404
+
+
+failures:
+    missing
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
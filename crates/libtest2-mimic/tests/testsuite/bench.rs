@@ -0,0 +1,71 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::bench("sort", |state| {
+                state.record_bench([1150, 1234, 2400]);
+                Ok(())
+            }),
+            Trial::bench("unmeasured", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn pretty() {
+    test_cmd()
+        .args(["--bench", "--test-threads", "1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test sort       ... bench:       1,234 ns/iter (+/- 1,250)
+test unmeasured ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--bench",
+            "--test-threads",
+            "1",
+            "sort",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"sort","mode":"bench","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"unmeasured","mode":"bench","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":1,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"sort","suite":"[..]"}
+{"event":"bench-complete","name":"sort","ns_per_iter":1234,"samples":[1150,1234,2400],"suite":"[..]"}
+{"event":"case-complete","name":"sort","mode":"bench","status":null,"message":null,"elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
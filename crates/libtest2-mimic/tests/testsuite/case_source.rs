@@ -0,0 +1,143 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Case;
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .case(Trial::test("cat", |_| Ok(())))
+        .case_source((0..4).rev().map(|i| {
+            Box::new(Trial::test(format!("generated::{i}"), |_| Ok(()))) as Box<dyn Case>
+        }))
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        0,
+        r#"
+running 5 tests
+test cat          ... ok
+test generated::0 ... ok
+test generated::1 ... ok
+test generated::2 ... ok
+test generated::3 ... ok
+
+test result: ok. 5 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 5 tests
+...
+
+test result: ok. 5 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn filtered() {
+    check(
+        &["--skip", "1"],
+        0,
+        r#"
+running 4 tests
+test cat          ... ok
+test generated::0 ... ok
+test generated::2 ... ok
+test generated::3 ... ok
+
+test result: ok. 4 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 4 tests
+...
+
+test result: ok. 4 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn in_subprocess() {
+    check(
+        &["-Zunstable-options", "--run-in-subprocess", "--skip", "1"],
+        0,
+        r#"
+running 4 tests
+test cat          ... ok
+test generated::0 ... ok
+test generated::2 ... ok
+test generated::3 ... ok
+
+test result: ok. 4 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 4 tests
+...
+
+test result: ok. 4 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn list_json() {
+    check(
+        &[
+            "-Zunstable-options",
+            "--format=json",
+            "--list",
+            "generated::1",
+        ],
+        0,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"generated::3","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::2","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::0","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::1","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":4,"suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"generated::3","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::2","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::0","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"generated::1","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":4,"suite":"[..]"}
+"#,
+    );
+}
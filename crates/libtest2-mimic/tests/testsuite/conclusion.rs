@@ -0,0 +1,58 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    let conclusion = libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Err(RunError::fail("was not a good boy"))),
+            Trial::test("fox", |_| Ok(())).ignore("sly"),
+        ])
+        .run()
+        .unwrap();
+    eprintln!("{conclusion:?}");
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn list() {
+    test_cmd()
+        .args(["--list", "--skip", "cat"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"dog: test
+fox: test (ignored)
+
+2 tests
+
+"#,
+        )
+        .stderr_matches(
+            r#"Conclusion { cases: ["dog", "fox"], num_filtered_out: 1, ran: false, num_passed: 0, num_failed: 0, num_errored: 0, num_ignored: 0, num_skipped: 0, interrupted: false, outcomes: [] }
+"#,
+        );
+}
+
+#[test]
+fn run() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(0)
+        .stderr_matches(
+            r#"Conclusion { cases: ["cat", "dog", "fox"], num_filtered_out: 0, ran: true, num_passed: 1, num_failed: 1, num_errored: 0, num_ignored: 1, num_skipped: 0, interrupted: false, outcomes: [CaseOutcome { name: "cat", status: None, message: None }, CaseOutcome { name: "dog", status: Some(Failed), message: Some("was not a good boy") }, CaseOutcome { name: "fox", status: Some(Ignored), message: Some("sly") }] }
+"#,
+        );
+}
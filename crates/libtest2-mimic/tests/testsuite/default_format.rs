@@ -0,0 +1,63 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .default_format(libtest2_mimic::OutputFormat::Terse)
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn default() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+..
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn overridden() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format",
+            "pretty",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test cat ... ok
+test dog ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
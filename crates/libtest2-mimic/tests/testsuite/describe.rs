@@ -0,0 +1,67 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("login", |_| Err(RunError::fail("locked out")))
+                .describe("user with an expired password is asked to reset it"),
+            Trial::test("logout", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn shown_with_failure() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+test login  ... FAILED
+test logout ... ok
+
+failures:
+
+---- login: user with an expired password is asked to reset it ----
+test.rs:7:13: locked out
+
+
+failures:
+    login
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args(["-Zunstable-options", "--format=json", "--list"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"login","mode":"test","run":true,"ignored":false,"description":"user with an expired password is asked to reset it","suite":"[..]"}
+{"event":"discover-case","name":"logout","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":0,"suite":"[..]"}
+"#,
+        );
+}
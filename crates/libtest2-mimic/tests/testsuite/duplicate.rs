@@ -0,0 +1,35 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+            Trial::test("cat", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn duplicate_name() {
+    test_cmd()
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"error: duplicate test name `cat`
+"#,
+        );
+}
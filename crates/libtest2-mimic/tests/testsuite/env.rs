@@ -0,0 +1,58 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("reads_env", |_| {
+                match std::env::var_os("LIBTEST2_SCOPED") {
+                    Some(value) => Err(RunError::fail(format!("saw {value:?}"))),
+                    None => Ok(()),
+                }
+            }),
+            Trial::test("sets_env", |state| {
+                let value = state.scoped_env("LIBTEST2_SCOPED", "1", || {
+                    std::env::var("LIBTEST2_SCOPED")
+                });
+                if value.as_deref() != Ok("1") {
+                    return Err(RunError::fail(format!("inside: {value:?}")));
+                }
+                match std::env::var_os("LIBTEST2_SCOPED") {
+                    Some(value) => Err(RunError::fail(format!("after: {value:?}"))),
+                    None => Ok(()),
+                }
+            })
+            .with_exclusive(true),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1).env_remove("LIBTEST2_SCOPED")
+}
+
+#[test]
+fn scoped_env() {
+    test_cmd()
+        .args(["--test-threads", "2"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test reads_env ... ok
+test sets_env  ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
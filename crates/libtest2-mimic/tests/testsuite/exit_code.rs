@@ -0,0 +1,64 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .failure_exit_code(1)
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Err(RunError::fail("was not a good boy"))),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn failure() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(1)
+        .stdout_matches(
+            r#"
+running 2 tests
+test cat ... ok
+test dog ... FAILED
+
+failures:
+
+---- dog ----
+test.rs:9:13: was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn success() {
+    test_cmd().args(["cat"]).assert().code(0).stdout_matches(
+        r#"
+running 1 test
+test cat ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+    );
+}
@@ -0,0 +1,85 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Case;
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+            Trial::test("fox", |_| Ok(())),
+            Trial::test("frog", |_| Ok(())),
+        ])
+        .filter(|case: &dyn Case| !case.name().starts_with('f'))
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        0,
+        r#"
+running 2 tests
+test cat ... ok
+test dog ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 2 tests
+...
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn skip() {
+    check(
+        &["--skip", "cat"],
+        0,
+        r#"
+running 1 test
+test dog ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 3 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 1 test
+...
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 3 filtered out; finished in [..]s
+
+"#,
+    );
+}
@@ -0,0 +1,248 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Err(RunError::fail("was not a good boy"))).ignore("fails"),
+            Trial::test("fox", |_| Ok(())).ignore(""),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        0,
+        r#"
+running 3 tests
+test cat ... ok
+test dog ... ignored, fails
+test fox ... ignored
+
+test result: ok. 1 passed; 0 failed; 2 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+test result: ok. 1 passed; 0 failed; 2 ignored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn include_ignored() {
+    check(
+        &["--include-ignored"],
+        101,
+        r#"
+running 3 tests
+test cat ... ok
+test dog ... FAILED
+test fox ... ok
+
+failures:
+
+---- dog ----
+test.rs:8:13: was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+failures:
+
+---- dog ----
+test.rs:8:13: was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn ignored() {
+    check(
+        &["--ignored"],
+        101,
+        r#"
+running 2 tests
+test dog ... FAILED
+test fox ... ok
+
+failures:
+
+---- dog ----
+test.rs:8:13: was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 2 tests
+...
+
+failures:
+
+---- dog ----
+test.rs:8:13: was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn ignored_list() {
+    check(
+        &["--ignored", "--list"],
+        0,
+        r#"dog: test (ignored)
+fox: test (ignored)
+
+2 tests
+
+"#,
+        r#"dog: test (ignored)
+fox: test (ignored)
+
+2 tests
+
+"#,
+    );
+}
+
+#[test]
+fn list() {
+    check(
+        &["--list"],
+        0,
+        r#"cat: test
+dog: test (ignored)
+fox: test (ignored)
+
+3 tests
+
+"#,
+        r#"cat: test
+dog: test (ignored)
+fox: test (ignored)
+
+3 tests
+
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn list_json() {
+    check(
+        &["-Zunstable-options", "--format=json", "--list"],
+        0,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":true,"ignored":true,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":true,"ignored":true,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":3,"num_filtered_out":0,"suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":true,"ignored":true,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":true,"ignored":true,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":3,"num_filtered_out":0,"suite":"[..]"}
+"#,
+    );
+}
+
+#[test]
+fn dry_run() {
+    check(
+        &["-Zunstable-options", "--dry-run", "--skip", "fox"],
+        0,
+        r#"cat (will run)
+dog (ignored)
+fox (filtered)
+
+1 will run; 1 ignored; 1 filtered out
+"#,
+        r#"cat (will run)
+dog (ignored)
+fox (filtered)
+
+1 will run; 1 ignored; 1 filtered out
+"#,
+    );
+}
+
+#[test]
+fn dry_run_include_ignored() {
+    check(
+        &["-Zunstable-options", "--dry-run", "--include-ignored"],
+        0,
+        r#"cat (will run)
+dog (will run)
+fox (will run)
+
+3 will run; 0 ignored; 0 filtered out
+"#,
+        r#"cat (will run)
+dog (will run)
+fox (will run)
+
+3 will run; 0 ignored; 0 filtered out
+"#,
+    );
+}
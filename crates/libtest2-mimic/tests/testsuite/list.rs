@@ -0,0 +1,159 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("smoke", |_| Ok(())),
+            Trial::test("parse::empty", |_| Ok(())),
+            Trial::test("parse::nested::deep", |_| Ok(())),
+            Trial::test("parse::nested::shallow", |_| Ok(())).ignore("slow"),
+            Trial::test("render::html", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn flat() {
+    test_cmd().args(["--list"]).assert().code(0).stdout_matches(
+        r#"parse::empty: test
+parse::nested::deep: test
+parse::nested::shallow: test (ignored)
+render::html: test
+smoke: test
+
+5 tests
+
+"#,
+    );
+}
+
+#[test]
+fn tree() {
+    test_cmd()
+        .args(["-Zunstable-options", "--list", "--format=pretty"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"parse::
+    nested::
+        deep: test
+        shallow: test (ignored)
+    empty: test
+render::
+    html: test
+smoke: test
+
+5 tests
+
+"#,
+        );
+}
+
+#[test]
+fn tree_filtered() {
+    test_cmd()
+        .args(["-Zunstable-options", "--list", "--format=pretty", "nested"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"parse::
+    nested::
+        deep: test
+        shallow: test (ignored)
+
+2 tests
+
+"#,
+        );
+}
+
+#[test]
+fn list_format_requires_unstable() {
+    test_cmd()
+        .args(["--list", "--list-format=plain"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--list-format` requires `-Zunstable-options`
+"#,
+        );
+}
+
+#[test]
+fn list_format_plain() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--list",
+            "--list-format=plain",
+            "--format=pretty",
+            "parse",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"parse::empty
+parse::nested::deep
+parse::nested::shallow
+"#,
+        );
+}
+
+#[test]
+fn list_format_terse() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--list",
+            "--list-format=terse",
+            "--format=json",
+            "render",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"render::html: test
+
+1 tests
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn list_format_json() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--list",
+            "--list-format=json",
+            "--format=terse",
+            "render",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"parse::empty","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"parse::nested::deep","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"parse::nested::shallow","mode":"test","run":false,"ignored":true,"suite":"[..]"}
+{"event":"discover-case","name":"render::html","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"smoke","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":4,"suite":"[..]"}
+"#,
+        );
+}
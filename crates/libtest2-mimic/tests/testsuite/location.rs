@@ -0,0 +1,92 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("add", |_| {
+                Err(RunError::fail("expected `3`, got `4`").at("cases/add.txt", 3))
+            }),
+            Trial::test("sub", |_| Err(RunError::fail("expected `1`, got `0`"))),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+const EXPECTED: &str = r#"
+running 2 tests
+test add ... FAILED
+test sub ... FAILED
+
+failures:
+
+---- add ----
+cases/add.txt:3: expected `3`, got `4`
+
+---- sub ----
+test.rs:10:13: expected `1`, got `0`
+
+
+failures:
+    add
+    sub
+
+test result: FAILED. 0 passed; 2 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#;
+
+#[test]
+fn normal() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(EXPECTED);
+}
+
+#[test]
+fn subprocess() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--run-in-subprocess",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(EXPECTED);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args(["-Zunstable-options", "--format=json", "--test-threads=1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"add","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"sub","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":0,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"add","suite":"[..]"}
+{"event":"case-complete","name":"add","mode":"test","status":"failed","message":"expected `3`, got `4`","elapsed_s":"[..]","failure_location":"cases/add.txt:3","suite":"[..]"}
+{"event":"case-start","name":"sub","suite":"[..]"}
+{"event":"case-complete","name":"sub","mode":"test","status":"failed","message":"expected `1`, got `0`","elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
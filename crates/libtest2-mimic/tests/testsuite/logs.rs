@@ -0,0 +1,76 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test_with_deps(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("fails", |_| {
+                tracing::info!(attempt = 1, "connecting");
+                Err(RunError::fail("refused"))
+            }),
+            Trial::test("passes", |_| {
+                tracing::info!("connecting");
+                Ok(())
+            }),
+        ])
+        .main();
+}
+"#,
+                false,
+                r#"libtest2-mimic.features = ["tracing"]
+tracing = "0.1.37"
+"#,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+const EXPECTED: &str = r#"
+running 2 tests
+test fails  ... FAILED
+test passes ... ok
+
+failures:
+
+---- fails ----
+test.rs:7:13: refused
+
+---- fails logs ----
+INFO [..]: connecting attempt=1
+
+
+failures:
+    fails
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#;
+
+#[test]
+fn failure_logs() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(EXPECTED);
+}
+
+#[test]
+fn failure_logs_in_subprocess() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--run-in-subprocess",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(EXPECTED);
+}
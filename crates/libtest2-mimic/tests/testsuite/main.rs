@@ -1,7 +1,34 @@
 mod all_passing;
+mod async_case;
+mod bench;
+mod case_source;
+mod conclusion;
+mod default_format;
+mod describe;
+mod duplicate;
+mod env;
+mod exit_code;
+mod filter;
+mod ignored;
+mod list;
+mod location;
+#[cfg(feature = "tracing")]
+mod logs;
 mod main_thread;
 mod mixed_bag;
+mod order;
 mod panic;
+mod run_to_events;
+mod setup;
+mod shuffle;
+mod state;
+mod subprocess;
+mod subtotals;
+mod suite_name;
+mod tags;
+mod terse;
+mod unstable;
 mod util;
+mod with_opts;
 
 pub use util::*;
@@ -18,8 +18,7 @@ fn main() {
         false,
     );
     let bin = crate::util::compile_test(&package_root);
-    snapbox::cmd::Command::new(bin)
-        .current_dir(package_root)
+    crate::util::test_bin(&bin, &package_root)
         .assert()
         .success()
         .stdout_matches(
@@ -28,3 +27,39 @@ running 1 test
 ...",
         );
 }
+
+#[test]
+fn check_main_thread_case_while_parallel() {
+    let package_root = crate::util::new_test(
+        r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    let outer_thread = std::thread::current().id();
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("check", move |_| {
+                assert_eq!(outer_thread, std::thread::current().id());
+                Ok(())
+            })
+            .with_main_thread(true),
+            Trial::test("other", move |_| {
+                assert_ne!(outer_thread, std::thread::current().id());
+                Ok(())
+            }),
+        ])
+        .main();
+}
+"#,
+        false,
+    );
+    let bin = crate::util::compile_test(&package_root);
+    crate::util::test_bin(&bin, &package_root)
+        .args(["--test-threads", "2"])
+        .assert()
+        .success()
+        .stdout_matches(
+            "
+running 2 tests
+...",
+        );
+}
@@ -40,7 +40,7 @@ fn main() {
             let bin = crate::util::compile_test(&package_root);
             (bin, package_root)
         });
-    snapbox::cmd::Command::new(&BIN.0).current_dir(&BIN.1)
+    crate::util::test_bin(&BIN.0, &BIN.1)
 }
 
 fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
@@ -64,19 +64,19 @@ fn normal() {
         101,
         r#"
 running 8 tests
-test bear  ... ignored
-test bunny ... ignored
+test bear  ... ignored, fails
+test bunny ... ignored, fails
 test cat   ... ok
 test dog   ... FAILED
-test fly   ... ignored
+test fly   ... ignored, fails
 test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
+test frog  ... ignored, slow
+test owl   ... ignored, fails
 
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -92,7 +92,7 @@ running 8 tests
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -111,19 +111,19 @@ fn test_mode() {
         101,
         r#"
 running 8 tests
-test bear  ... ignored
-test bunny ... ignored
+test bear  ... ignored, fails
+test bunny ... ignored, fails
 test cat   ... ok
 test dog   ... FAILED
-test fly   ... ignored
+test fly   ... ignored, fails
 test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
+test frog  ... ignored, slow
+test owl   ... ignored, fails
 
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -139,7 +139,7 @@ running 8 tests
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -155,44 +155,17 @@ test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in
 fn bench_mode() {
     check(
         &["--bench"],
-        101,
+        0,
         r#"
-running 8 tests
-test bear  ... ignored
-test bunny ... ignored
-test cat   ... ok
-test dog   ... FAILED
-test fly   ... ignored
-test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
+running 0 tests
 
-failures:
-
----- dog ----
-was not a good boy
-
-
-failures:
-    dog
-
-test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 8 tests
-...
-
-failures:
+running 0 tests
 
----- dog ----
-was not a good boy
-
-
-failures:
-    dog
-
-test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
     )
@@ -235,28 +208,12 @@ fn list_ignored() {
     check(
         &["--list", "--ignored"],
         0,
-        r#"bear: test
-bunny: test
-cat: test
-dog: test
-fly: test
-fox: test
-frog: test
-owl: test
-
-8 tests
+        r#"
+0 tests
 
 "#,
-        r#"bear: test
-bunny: test
-cat: test
-dog: test
-fly: test
-fox: test
-frog: test
-owl: test
-
-8 tests
+        r#"
+0 tests
 
 "#,
     );
@@ -289,7 +246,7 @@ fn filter_c() {
         0,
         r#"
 running 2 tests
-test bear ... ignored
+test bear ... ignored, fails
 test cat  ... ok
 
 test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
@@ -312,7 +269,7 @@ fn filter_o_test() {
         0,
         r#"
 running 2 tests
-test bear ... ignored
+test bear ... ignored, fails
 test cat  ... ok
 
 test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
@@ -343,10 +300,10 @@ test owl  ... FAILED
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 ---- owl ----
-broke neck
+test.rs:18:13: broke neck
 
 
 failures:
@@ -363,10 +320,10 @@ running 4 tests
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 ---- owl ----
-broke neck
+test.rs:18:13: broke neck
 
 
 failures:
@@ -383,51 +340,20 @@ test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in
 fn filter_o_test_ignored() {
     check(
         &["--test", "--ignored", "o"],
-        101,
+        0,
         r#"
-running 4 tests
-test dog  ... FAILED
-test fox  ... ok
-test frog ... ok
-test owl  ... FAILED
+running 0 tests
 
-failures:
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
-
-failures:
-    dog
-    owl
-
-test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 4 tests
-...
-
-failures:
+running 0 tests
 
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
-
-failures:
-    dog
-    owl
-
-test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
-    )
+    );
 }
 
 #[test]
@@ -449,16 +375,16 @@ test owl   ... FAILED
 failures:
 
 ---- bear ----
-no honey
+test.rs:26:13: no honey
 
 ---- bunny ----
-jumped too high
+test.rs:10:13: jumped too high
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 ---- owl ----
-broke neck
+test.rs:18:13: broke neck
 
 
 failures:
@@ -477,16 +403,16 @@ running 8 tests
 failures:
 
 ---- bear ----
-no honey
+test.rs:26:13: no honey
 
 ---- bunny ----
-jumped too high
+test.rs:10:13: jumped too high
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 ---- owl ----
-broke neck
+test.rs:18:13: broke neck
 
 
 failures:
@@ -505,112 +431,40 @@ test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in
 fn normal_ignored() {
     check(
         &["--ignored"],
-        101,
+        0,
         r#"
-running 8 tests
-test bear  ... FAILED
-test bunny ... FAILED
-test cat   ... ok
-test dog   ... FAILED
-test fly   ... ok
-test fox   ... ok
-test frog  ... ok
-test owl   ... FAILED
-
-failures:
+running 0 tests
 
----- bear ----
-no honey
-
----- bunny ----
-jumped too high
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
-
-failures:
-    bear
-    bunny
-    dog
-    owl
-
-test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 8 tests
-...
-
-failures:
-
----- bear ----
-no honey
-
----- bunny ----
-jumped too high
+running 0 tests
 
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
-
-failures:
-    bear
-    bunny
-    dog
-    owl
-
-test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
-    )
+    );
 }
 
 #[test]
 fn lots_of_flags() {
     check(
         &["--ignored", "--skip", "g", "--test", "o"],
-        101,
+        0,
         r#"
-running 2 tests
-test fox ... ok
-test owl ... FAILED
-
-failures:
-
----- owl ----
-broke neck
+running 0 tests
 
-
-failures:
-    owl
-
-test result: FAILED. 1 passed; 1 failed; 0 ignored; 6 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 2 tests
-...
+running 0 tests
 
-failures:
-
----- owl ----
-broke neck
-
-
-failures:
-    owl
-
-test result: FAILED. 1 passed; 1 failed; 0 ignored; 6 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
-    )
+    );
 }
 
 #[test]
@@ -619,27 +473,27 @@ fn list_json() {
     check(
         &["-Zunstable-options", "--format=json", "--list", "a"],
         0,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-"#,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
 "#,
     )
 }
@@ -650,39 +504,39 @@ fn test_json() {
     check(
         &["-Zunstable-options", "--format=json", "a"],
         0,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-{"event":"suite-start"}
-{"event":"case-start","name":"bear"}
-{"event":"case-complete","name":"bear","mode":"test","status":"ignored","message":"fails","elapsed_s":"[..]"}
-{"event":"case-start","name":"cat"}
-{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]"}
-{"event":"suite-complete","elapsed_s":"[..]"}
-"#,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-{"event":"suite-start"}
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"bear","suite":"[..]"}
+{"event":"case-complete","name":"bear","mode":"test","status":"ignored","message":"fails","elapsed_s":"[..]","suite":"[..]"}
+{"event":"case-start","name":"cat","suite":"[..]"}
+{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
 [..]
 [..]
 [..]
 [..]
-{"event":"suite-complete","elapsed_s":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
 "#,
     )
 }
@@ -716,8 +570,11 @@ fn test_junit() {
         0,
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <testsuites>
-<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" >
-<testcase classname="crate" name="cat" time="0.000s"/>
+<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="crate" name="bear" time="0.000s">
+<skipped message="fails"/>
+</testcase>
+...
 <system-out/>
 <system-err/>
 </testsuite>
@@ -725,8 +582,8 @@ fn test_junit() {
 "#,
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <testsuites>
-<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" >
-<testcase classname="crate" name="cat" time="0.000s"/>
+<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" time="[..]" timestamp="[..]" hostname="[..]" >
+...
 <system-out/>
 <system-err/>
 </testsuite>
@@ -746,7 +603,7 @@ ii.Fi.ii
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -761,7 +618,7 @@ running 8 tests
 failures:
 
 ---- dog ----
-was not a good boy
+test.rs:8:13: was not a good boy
 
 
 failures:
@@ -778,26 +635,26 @@ fn shuffle() {
     check(
         &["-Zunstable-options", "--list", "--shuffle-seed=1"],
         0,
-        r#"fox: test
-cat: test
-fly: test
+        r#"owl: test
 bear: test
-owl: test
+fox: test
+dog: test
 frog: test
+fly: test
+cat: test
 bunny: test
-dog: test
 
 8 tests
 
 "#,
-        r#"fox: test
-cat: test
-fly: test
+        r#"owl: test
 bear: test
-owl: test
+fox: test
+dog: test
 frog: test
+fly: test
+cat: test
 bunny: test
-dog: test
 
 8 tests
 
@@ -806,26 +663,26 @@ dog: test
     check(
         &["-Zunstable-options", "--list", "--shuffle-seed=2"],
         0,
-        r#"owl: test
+        r#"bunny: test
+fly: test
 dog: test
-fox: test
 frog: test
-bear: test
-fly: test
-bunny: test
 cat: test
+bear: test
+fox: test
+owl: test
 
 8 tests
 
 "#,
-        r#"owl: test
+        r#"bunny: test
+fly: test
 dog: test
-fox: test
 frog: test
-bear: test
-fly: test
-bunny: test
 cat: test
+bear: test
+fox: test
+owl: test
 
 8 tests
 
@@ -0,0 +1,90 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("fox", |_| Ok(())),
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn name() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--order",
+            "name",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 3 tests
+test cat ... ok
+test dog ... ok
+test fox ... ok
+
+test result: ok. 3 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn declared() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--order",
+            "declared",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 3 tests
+test fox ... ok
+test cat ... ok
+test dog ... ok
+
+test result: ok. 3 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn list_declared() {
+    test_cmd()
+        .args(["-Zunstable-options", "--order", "declared", "--list"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"fox: test
+cat: test
+dog: test
+
+3 tests
+
+"#,
+        );
+}
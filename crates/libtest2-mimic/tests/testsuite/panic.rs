@@ -18,7 +18,7 @@ fn main() {
             let bin = crate::util::compile_test(&package_root);
             (bin, package_root)
         });
-    snapbox::cmd::Command::new(&BIN.0).current_dir(&BIN.1)
+    crate::util::test_bin(&BIN.0, &BIN.1)
 }
 
 fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
@@ -48,7 +48,7 @@ test passes ... ok
 failures:
 
 ---- panics ----
-test panicked: uh oh
+test.rs:7:39: test panicked: uh oh
 
 
 failures:
@@ -64,7 +64,7 @@ running 2 tests
 failures:
 
 ---- panics ----
-test panicked: uh oh
+test.rs:7:39: test panicked: uh oh
 
 
 failures:
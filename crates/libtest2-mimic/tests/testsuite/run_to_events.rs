@@ -0,0 +1,38 @@
+use libtest2_mimic::Event;
+use libtest2_mimic::RunError;
+use libtest2_mimic::RunStatus;
+use libtest2_mimic::Trial;
+
+#[test]
+fn run_to_events() {
+    let events = libtest2_mimic::Harness::with_args(["test", "--test-threads", "1"])
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Err(RunError::fail("was not a good boy"))),
+            Trial::test("fox", |_| Ok(())).ignore("sly"),
+        ])
+        .run_to_events()
+        .unwrap();
+    let completed = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::CaseComplete {
+                name,
+                status,
+                message,
+                ..
+            } => Some((name.as_str(), *status, message.as_deref())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        completed,
+        [
+            ("cat", None, None),
+            ("dog", Some(RunStatus::Failed), Some("was not a good boy")),
+            ("fox", Some(RunStatus::Ignored), Some("sly")),
+        ]
+    );
+    assert!(matches!(events.first(), Some(Event::DiscoverStart)));
+    assert!(matches!(events.last(), Some(Event::SuiteComplete { .. })));
+}
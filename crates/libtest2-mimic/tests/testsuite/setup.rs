@@ -0,0 +1,59 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .try_add(|| -> std::io::Result<_> {
+            Ok(vec![Trial::test("cat", |_| Ok(()))])
+        })
+        .try_add(|| -> std::io::Result<Vec<Trial>> {
+            if std::env::var_os("BREAK_SETUP").is_some() {
+                return Err(std::io::Error::other("could not read `fixtures/`"));
+            }
+            Ok(vec![Trial::test("dog", |_| Ok(()))])
+        })
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn setup_succeeds() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test cat ... ok
+test dog ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn setup_fails() {
+    test_cmd()
+        .env("BREAK_SETUP", "1")
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"could not read `fixtures/`
+"#,
+        );
+}
@@ -0,0 +1,119 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .shuffle_seed(1)
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+            Trial::test("fox", |_| Ok(())),
+            Trial::test("owl", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn harness_seed() {
+    test_cmd().args(["--list"]).assert().code(0).stdout_matches(
+        r#"owl: test
+cat: test
+fox: test
+dog: test
+
+4 tests
+
+"#,
+    );
+}
+
+#[test]
+fn cli_seed_overrides() {
+    test_cmd()
+        .args(["-Zunstable-options", "--list", "--shuffle-seed=3"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"fox: test
+dog: test
+cat: test
+owl: test
+
+4 tests
+
+"#,
+        );
+}
+
+fn shuffle_with_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .shuffle_with(|seed, cases| cases.rotate_left(seed as usize % cases.len()))
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+            Trial::test("fox", |_| Ok(())),
+            Trial::test("owl", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn custom_shuffle() {
+    shuffle_with_cmd()
+        .args(["-Zunstable-options", "--list", "--shuffle-seed=1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"dog: test
+fox: test
+owl: test
+cat: test
+
+4 tests
+
+"#,
+        );
+}
+
+#[test]
+fn custom_shuffle_unused_without_seed() {
+    shuffle_with_cmd()
+        .args(["--list"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"cat: test
+dog: test
+fox: test
+owl: test
+
+4 tests
+
+"#,
+        );
+}
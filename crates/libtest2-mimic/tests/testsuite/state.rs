@@ -0,0 +1,188 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("config", |state| {
+                Err(RunError::fail(format!(
+                    "mode={:?} nocapture={} threads={}",
+                    state.run_mode(),
+                    state.is_nocapture(),
+                    state.test_threads(),
+                )))
+            }),
+            Trial::test("deadline", |state| {
+                while state.deadline().is_some() {
+                    state.check_deadline()?;
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                Ok(())
+            }),
+            Trial::test("own_timeout", |state| {
+                while state.deadline().is_some() {
+                    state.check_deadline()?;
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                Ok(())
+            })
+            .timeout(std::time::Duration::from_millis(100)),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn config() {
+    test_cmd()
+        .args(["--test-threads", "3", "config"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 1 test
+test config ... FAILED
+
+failures:
+
+---- config ----
+test.rs:7:13: mode=Test nocapture=false threads=3
+
+
+failures:
+    config
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn deadline() {
+    test_cmd()
+        .args(["-Zunstable-options", "--timeout", "0.1", "deadline"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 1 test
+test deadline ... FAILED
+
+failures:
+
+---- deadline ----
+test.rs:15:13: exceeded timeout of 0.1s
+
+
+failures:
+    deadline
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn no_deadline() {
+    test_cmd()
+        .args(["deadline"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test deadline ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn case_timeout() {
+    test_cmd()
+        .args(["-Zunstable-options", "--timeout", "60", "own_timeout"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 1 test
+test own_timeout ... FAILED
+
+failures:
+
+---- own_timeout ----
+test.rs:22:13: exceeded timeout of 0.1s
+
+
+failures:
+    own_timeout
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn record_json() {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("plain", |_| Ok(())),
+            Trial::test("records", |state| {
+                state.record("seed", 42);
+                state.record("backend", "sqlite");
+                Ok(())
+            }),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--quiet",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-complete",[..]}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"plain","suite":"[..]"}
+{"event":"case-complete","name":"plain","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"[..]"}
+{"event":"case-start","name":"records","suite":"[..]"}
+{"event":"case-complete","name":"records","mode":"test","status":null,"message":null,"elapsed_s":"[..]","metadata":{"backend":"sqlite","seed":"42"},"suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
@@ -0,0 +1,225 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("aborts", |_| std::process::abort()),
+            Trial::test("ignored", |_| Ok(())).ignore("slow"),
+            Trial::test("panics", |_| panic!("uh oh")),
+            Trial::test("passes", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn run_in_subprocess() {
+    check(
+        &["-Zunstable-options", "--run-in-subprocess"],
+        101,
+        r#"
+running 4 tests
+test aborts  ... FAILED
+test ignored ... ignored, slow
+test panics  ... FAILED
+test passes  ... ok
+
+failures:
+
+---- aborts ----
+test.rs:6:13: process exited with [..]
+
+---- panics ----
+test.rs:8:39: test panicked: uh oh
+
+
+failures:
+    aborts
+    panics
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 4 tests
+...
+
+failures:
+
+---- aborts ----
+test.rs:6:13: process exited with [..]
+
+---- panics ----
+test.rs:8:39: test panicked: uh oh
+
+
+failures:
+    aborts
+    panics
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn run_in_subprocess_include_ignored() {
+    check(
+        &[
+            "-Zunstable-options",
+            "--run-in-subprocess",
+            "--include-ignored",
+            "--skip",
+            "abort",
+        ],
+        101,
+        r#"
+running 3 tests
+test ignored ... ok
+test panics  ... FAILED
+test passes  ... ok
+
+failures:
+
+---- panics ----
+test.rs:8:39: test panicked: uh oh
+
+
+failures:
+    panics
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+failures:
+
+---- panics ----
+test.rs:8:39: test panicked: uh oh
+
+
+failures:
+    panics
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn run_in_subprocess_ignored() {
+    check(
+        &["-Zunstable-options", "--run-in-subprocess", "--ignored"],
+        0,
+        r#"
+running 1 test
+test ignored ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 3 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 1 test
+test ignored ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 3 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn run_in_subprocess_nocapture() {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("partial", |_| {
+                print!("no newline");
+                Ok(())
+            }),
+            Trial::test("stderr", |_| {
+                eprintln!("uh oh");
+                Err(libtest2_mimic::RunError::fail("failed"))
+            }),
+            Trial::test("stdout", |_| {
+                println!("one");
+                println!();
+                println!("two");
+                Ok(())
+            }),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+        .args([
+            "-Zunstable-options",
+            "--run-in-subprocess",
+            "--format=json",
+            "--quiet",
+            "--nocapture",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-complete",[..]}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"partial","suite":"[..]"}
+{"event":"case-output","name":"partial","stream":"stdout","line":"no newline","suite":"[..]"}
+{"event":"case-complete","name":"partial",[..]}
+{"event":"case-start","name":"stderr","suite":"[..]"}
+{"event":"case-output","name":"stderr","stream":"stderr","line":"uh oh","suite":"[..]"}
+{"event":"case-complete","name":"stderr",[..]}
+{"event":"case-start","name":"stdout","suite":"[..]"}
+{"event":"case-output","name":"stdout","stream":"stdout","line":"one","suite":"[..]"}
+{"event":"case-output","name":"stdout","stream":"stdout","line":"","suite":"[..]"}
+{"event":"case-output","name":"stdout","stream":"stdout","line":"two","suite":"[..]"}
+{"event":"case-complete","name":"stdout",[..]}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
@@ -0,0 +1,75 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    use libtest2_mimic::RunError;
+    libtest2_mimic::Harness::with_env()
+        .cases(vec![
+            Trial::test("net::connect", |_| Ok(())),
+            Trial::test("net::resolve", |_| Err(RunError::fail("no dns"))),
+            Trial::test("parse::ipv6", |_| Ok(())),
+            Trial::test("parse::ipv4", |_| Ok(())).ignore(""),
+            Trial::test("smoke", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn subtotals() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--subtotals",
+            "--quiet",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 5 tests
+.Fi..
+failures:
+
+---- net::resolve ----
+test.rs:8:13: no dns
+
+
+failures:
+    net::resolve
+
+subtotals:
+    crate: 1 passed; 0 failed; 0 ignored
+    net: 1 passed; 1 failed; 0 ignored
+    parse: 1 passed; 0 failed; 1 ignored
+
+test result: FAILED. 3 passed; 1 failed; 1 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--subtotals"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--subtotals` requires `-Zunstable-options`
+"#,
+        );
+}
@@ -0,0 +1,112 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .suite_name("animals")
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        0,
+        r#"
+animals: running 2 tests
+test cat ... ok
+test dog ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+animals: running 2 tests
+...
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_json() {
+    check(
+        &["-Zunstable-options", "--format=json"],
+        0,
+        r#"{"event":"discover-start","suite":"animals"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"animals"}
+{"event":"discover-case","name":"dog","mode":"test","run":true,"ignored":false,"suite":"animals"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":0,"suite":"animals"}
+{"event":"suite-start","suite":"animals"}
+{"event":"case-start","name":"cat","suite":"animals"}
+{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"animals"}
+{"event":"case-start","name":"dog","suite":"animals"}
+{"event":"case-complete","name":"dog","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"animals"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"animals"}
+"#,
+        r#"{"event":"discover-start","suite":"animals"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"animals"}
+{"event":"discover-case","name":"dog","mode":"test","run":true,"ignored":false,"suite":"animals"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":0,"suite":"animals"}
+{"event":"suite-start","suite":"animals"}
+[..]
+[..]
+[..]
+[..]
+{"event":"suite-complete","elapsed_s":"[..]","suite":"animals"}
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "junit")]
+fn junit_classname() {
+    test_cmd()
+        .args(["-Zunstable-options", "--format=junit", "--test-threads=1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="0" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="animals" name="cat" time="[..]s"/>
+<testcase classname="animals" name="dog" time="[..]s"/>
+<system-out/>
+<system-err/>
+</testsuite>
+</testsuites>
+"#,
+        );
+}
@@ -0,0 +1,36 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    libtest2_mimic::Harness::with_env()
+        .cases((0..150).map(|i| Trial::test(format!("case{i:03}"), |_| Ok(()))))
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn wraps() {
+    test_cmd()
+        .args(["--quiet"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 150 tests
+....................................................................................................
+..................................................
+test result: ok. 150 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
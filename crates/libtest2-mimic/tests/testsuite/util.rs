@@ -1,4 +1,9 @@
 pub fn new_test(test: &str, harness: bool) -> std::path::PathBuf {
+    new_test_with_deps(test, harness, "")
+}
+
+/// Like [`new_test`], adding `deps` to `[dev-dependencies]`
+pub fn new_test_with_deps(test: &str, harness: bool, deps: &str) -> std::path::PathBuf {
     static SUFFIX: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
     let suffix = SUFFIX.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
     let target_name = format!("t{suffix}");
@@ -25,6 +30,7 @@ path = "lib.rs"
 
 [dev-dependencies]
 libtest2-mimic.path = "{mimic_relpath}"
+{deps}
 
 [[test]]
 name = "{target_name}"
@@ -50,6 +56,13 @@ pub fn compile_test(package_root: &std::path::Path) -> std::path::PathBuf {
     tests::compile_test(&manifest_path, target_name, args)
 }
 
+/// Run the test binary from [`compile_test`], without the color settings of the calling `cargo`
+pub fn test_bin(bin: &std::path::Path, package_root: &std::path::Path) -> snapbox::cmd::Command {
+    snapbox::cmd::Command::new(bin)
+        .current_dir(package_root)
+        .env_remove("CARGO_TERM_COLOR")
+}
+
 fn mimic_relpath(root: &std::path::Path) -> std::path::PathBuf {
     let current_dir = std::env::current_dir().unwrap();
     let relpath = pathdiff::diff_paths(current_dir, root).unwrap();
@@ -0,0 +1,45 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+fn main() {
+    use libtest2_mimic::Trial;
+    let opts = libtest2_mimic::TestOpts {
+        filters: vec!["a".to_owned()],
+        test_threads: std::num::NonZeroUsize::new(1),
+        format: libtest2_mimic::OutputFormat::Terse,
+        ..Default::default()
+    };
+    libtest2_mimic::Harness::with_opts(opts)
+        .cases(vec![
+            Trial::test("cat", |_| Ok(())),
+            Trial::test("dog", |_| Ok(())),
+            Trial::test("bat", |_| Ok(())),
+        ])
+        .main();
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn args_are_ignored() {
+    test_cmd()
+        .args(["--format", "pretty", "dog"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+..
+test result: ok. 2 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+}
@@ -1,10 +1,11 @@
 use libtest2::RunError;
 use libtest2::RunResult;
+use libtest2::State;
 use libtest2::Trial;
 
-fn main() -> std::io::Result<()> {
-    let tests = collect_tests()?;
-    libtest2::Harness::with_env().cases(tests).main()
+fn main() {
+    // Not being able to find the files is a problem with the suite, not any one test
+    libtest2::Harness::with_env().try_add(collect_tests).main()
 }
 
 /// Creates one test for each `.rs` file in the current directory or
@@ -30,7 +31,7 @@ fn collect_tests() -> std::io::Result<Vec<Trial>> {
                     .to_string_lossy()
                     .into_owned();
 
-                    let test = Trial::test(name, move |_| check_file(&path));
+                    let test = Trial::test(name, move |state| check_file(state, &path));
                     tests.push(test);
                 }
             } else if file_type.is_dir() {
@@ -52,7 +53,7 @@ fn collect_tests() -> std::io::Result<Vec<Trial>> {
 }
 
 /// Performs a couple of tidy tests.
-fn check_file(path: &std::path::Path) -> RunResult {
+fn check_file(state: &State, path: &std::path::Path) -> RunResult {
     let content =
         std::fs::read(path).map_err(|e| RunError::fail(format_args!("Cannot read file: {e}")))?;
 
@@ -61,23 +62,23 @@ fn check_file(path: &std::path::Path) -> RunResult {
         .map_err(|_| RunError::fail("The file's contents are not a valid UTF-8 string!"))?;
 
     // Check for `\r`: we only want `\n` line breaks!
-    if content.contains('\r') {
-        return Err(RunError::fail(
-            "Contains '\\r' chars. Please use ' \\n' line breaks only!",
-        ));
-    }
+    state.assert(!content.contains('\r'), || {
+        "Contains '\\r' chars. Please use ' \\n' line breaks only!"
+    })?;
 
     // Check for tab characters `\t`
-    if content.contains('\t') {
-        return Err(RunError::fail(
-            "Contains tab characters ('\\t'). Indent with four spaces!",
-        ));
-    }
+    state.assert(!content.contains('\t'), || {
+        "Contains tab characters ('\\t'). Indent with four spaces!"
+    })?;
 
     // Check for too long lines
-    if content.lines().any(|line| line.chars().count() > 100) {
-        return Err(RunError::fail("Contains lines longer than 100 codepoints!"));
-    }
+    let longest = content.lines().map(|line| line.chars().count()).max();
+    state.assert(longest.unwrap_or(0) <= 100, || {
+        format!(
+            "Contains a line of {} codepoints, over 100!",
+            longest.unwrap_or(0)
+        )
+    })?;
 
     Ok(())
 }
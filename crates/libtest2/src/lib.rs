@@ -22,31 +22,159 @@
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(feature = "async")]
+pub use libtest2_harness::AsyncCase;
+pub use libtest2_harness::Case;
+pub use libtest2_harness::CaseOutcome;
+pub use libtest2_harness::Conclusion;
+pub use libtest2_harness::Event;
+#[cfg(feature = "async")]
+pub use libtest2_harness::Executor;
 pub use libtest2_harness::Harness;
+pub use libtest2_harness::OutputFormat;
 pub use libtest2_harness::RunError;
+#[cfg(feature = "async")]
+pub use libtest2_harness::RunFuture;
+pub use libtest2_harness::RunMode;
 pub use libtest2_harness::RunResult;
+pub use libtest2_harness::RunStatus;
 pub use libtest2_harness::State;
 pub use libtest2_harness::TestKind;
+pub use libtest2_harness::TestOpts;
 
-use libtest2_harness::Case;
 use libtest2_harness::Source;
 
 pub struct Trial {
     name: String,
+    mode: RunMode,
+    main_thread: bool,
+    exclusive: bool,
+    source: Option<Source>,
+    ignored: Option<String>,
+    should_panic: Option<String>,
+    tags: Vec<&'static str>,
+    description: Option<String>,
+    timeout: Option<std::time::Duration>,
     #[allow(clippy::type_complexity)]
     runner: Box<dyn Fn(&State) -> Result<(), RunError> + Send + Sync>,
 }
 
 impl Trial {
+    /// The [`Source`] is the location of the caller
+    #[track_caller]
     pub fn test(
         name: impl Into<String>,
         runner: impl Fn(&State) -> Result<(), RunError> + Send + Sync + 'static,
     ) -> Self {
         Self {
             name: name.into(),
+            mode: RunMode::Test,
+            main_thread: false,
+            exclusive: false,
+            source: Some(caller_source()),
+            ignored: None,
+            should_panic: None,
+            tags: Vec::new(),
+            description: None,
+            timeout: None,
             runner: Box::new(runner),
         }
     }
+
+    /// The [`Source`] is the location of the caller
+    #[track_caller]
+    pub fn bench(
+        name: impl Into<String>,
+        runner: impl Fn(&State) -> Result<(), RunError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mode: RunMode::Bench,
+            main_thread: false,
+            exclusive: false,
+            source: Some(caller_source()),
+            ignored: None,
+            should_panic: None,
+            tags: Vec::new(),
+            description: None,
+            timeout: None,
+            runner: Box::new(runner),
+        }
+    }
+
+    /// Ignore this case unless ignored tests are requested
+    ///
+    /// `reason` may be empty.
+    pub fn ignore(mut self, reason: impl Into<String>) -> Self {
+        self.ignored = Some(reason.into());
+        self
+    }
+
+    /// Pass only if the case panics, with a message containing `expected`
+    ///
+    /// `expected` may be empty to accept any panic.
+    pub fn should_panic(mut self, expected: impl Into<String>) -> Self {
+        self.should_panic = Some(expected.into());
+        self
+    }
+
+    /// Label this case for `--include-tag` and `--exclude-tag`
+    ///
+    /// May be called multiple times to add more tags.
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// The scenario this case checks, see [`Case::describe`]
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// How long this case has to run, instead of `--timeout`
+    ///
+    /// See [`State::check_deadline`].
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Don't run other cases in parallel to this one
+    ///
+    /// This is for cases that change process-wide state other cases may read, like environment
+    /// variables, see [`State::scoped_env`].
+    pub fn with_exclusive(mut self, yes: bool) -> Self {
+        self.exclusive = yes;
+        self
+    }
+
+    /// Run on the thread that called [`Harness::main`], even when running tests in parallel
+    pub fn with_main_thread(mut self, yes: bool) -> Self {
+        self.main_thread = yes;
+        self
+    }
+
+    /// For [`libtest2_main!`], where the caller is the macro invocation rather than the test
+    #[doc(hidden)]
+    pub fn __without_source(mut self) -> Self {
+        self.source = None;
+        self
+    }
+}
+
+#[track_caller]
+fn caller_source() -> Source {
+    let location = std::panic::Location::caller();
+    let line = location.line() as usize;
+    let col = location.column() as usize;
+    Source::Rust {
+        source_file: std::path::PathBuf::from(location.file()),
+        start_line: line,
+        start_col: col,
+        end_line: line,
+        end_col: col,
+    }
 }
 
 impl Case for Trial {
@@ -56,26 +184,197 @@ impl Case for Trial {
     fn kind(&self) -> TestKind {
         Default::default()
     }
+    fn mode(&self) -> RunMode {
+        self.mode
+    }
     fn source(&self) -> Option<&Source> {
-        None
+        self.source.as_ref()
+    }
+    fn is_ignored(&self) -> Option<&str> {
+        self.ignored.as_deref()
+    }
+    fn tags(&self) -> &[&str] {
+        &self.tags
+    }
+    fn describe(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout
     }
     fn exclusive(&self, _: &State) -> bool {
-        false
+        self.exclusive
+    }
+    fn main_thread(&self) -> bool {
+        self.main_thread
     }
 
     fn run(&self, state: &State) -> Result<(), RunError> {
-        (self.runner)(state)
+        let Some(expected) = &self.should_panic else {
+            return (self.runner)(state);
+        };
+        let payload =
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (self.runner)(state))) {
+                Ok(Ok(())) => return Err(RunError::fail("test did not panic as expected")),
+                Ok(Err(err)) => return Err(err),
+                Err(payload) => payload,
+            };
+        let message = payload
+            .downcast_ref::<String>()
+            .map(|s| s.as_str())
+            .or_else(|| payload.downcast_ref::<&str>().copied());
+        match message {
+            _ if expected.is_empty() => Ok(()),
+            Some(message) if message.contains(expected.as_str()) => Ok(()),
+            Some(message) => Err(RunError::fail(format!(
+                "panic did not contain expected string
+      panic message: `{message:?}`,
+ expected substring: `{expected:?}`"
+            ))),
+            None => Err(RunError::fail(format!(
+                "expected panic with string value,
+ found non-string value
+     expected substring: `{expected:?}`"
+            ))),
+        }
     }
 }
 
+/// Fail the case with a formatted message, reporting the location of the call
+///
+/// ```
+/// fn check(state: &libtest2::State) -> libtest2::RunResult {
+///     let answer = 41;
+///     if answer != 42 {
+///         libtest2::bail!("wrong answer: {answer}");
+///     }
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! bail {
+    ($($arg:tt)+) => {
+        return ::std::result::Result::Err(
+            $crate::RunError::fail(::std::format!($($arg)+))
+                .at(::std::file!(), ::std::line!() as usize),
+        )
+    };
+}
+
+/// Fail the case unless `cond` holds, see [`bail!`]
+///
+/// Without a message, the condition is reported.
+///
+/// ```
+/// fn check(state: &libtest2::State) -> libtest2::RunResult {
+///     let (x, y) = (1, 1);
+///     libtest2::ensure!(x == y, "mismatch: {x} != {y}");
+///     libtest2::ensure!(0 < x);
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::bail!("condition failed: `{}`", ::std::stringify!($cond));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::bail!($($arg)+);
+        }
+    };
+}
+
 /// Expands to the test harness
+///
+/// Each test is a function taking [`State`] and returning `Result<(), E>`, where `E` converts
+/// into [`RunError`], like [`RunResult`] or `std::io::Result<()>`.
+///
+/// Tests may be preceded by attributes, like with `#[test]`:
+/// - `#[ignore]` or `#[ignore = "reason"]`, see [`Trial::ignore`]
+/// - `#[should_panic]` or `#[should_panic(expected = "message")]`, see [`Trial::should_panic`]
+///
+/// ```no_run
+/// libtest2::libtest2_main!(check, #[ignore = "slow"] long_check);
+///
+/// fn check(_state: &libtest2::State) -> libtest2::RunResult {
+///     Ok(())
+/// }
+///
+/// fn long_check(_state: &libtest2::State) -> libtest2::RunResult {
+///     Ok(())
+/// }
+/// ```
 #[macro_export]
 macro_rules! libtest2_main {
-    ( $( $test:path ),* $(,)*) => {
+    (@trial $trial:expr;) => {
+        $trial
+    };
+    (@trial $trial:expr; [ignore] $($rest:tt)*) => {
+        ::libtest2::libtest2_main!(@trial $trial.ignore(""); $($rest)*)
+    };
+    (@trial $trial:expr; [ignore = $reason:literal] $($rest:tt)*) => {
+        ::libtest2::libtest2_main!(@trial $trial.ignore($reason); $($rest)*)
+    };
+    (@trial $trial:expr; [should_panic] $($rest:tt)*) => {
+        ::libtest2::libtest2_main!(@trial $trial.should_panic(""); $($rest)*)
+    };
+    (@trial $trial:expr; [should_panic(expected = $expected:literal)] $($rest:tt)*) => {
+        ::libtest2::libtest2_main!(@trial $trial.should_panic($expected); $($rest)*)
+    };
+    (@trial $trial:expr; [$($attr:tt)*] $($rest:tt)*) => {
+        ::std::compile_error!(::std::concat!(
+            "unsupported attribute `#[",
+            ::std::stringify!($($attr)*),
+            "]`"
+        ))
+    };
+    ( $( $(#[$($attr:tt)*])* $test:path ),* $(,)*) => {
         fn main() {
             ::libtest2::Harness::with_env()
-                $(.case(::libtest2::Trial::test(::std::stringify!($test), $test)))*
+                $(.case(::libtest2::libtest2_main!(
+                    @trial ::libtest2::Trial::test(::std::stringify!($test), |state| {
+                        ::std::result::Result::map_err(
+                            $test(state),
+                            ::std::convert::Into::<::libtest2::RunError>::into,
+                        )
+                    }).__without_source();
+                    $([$($attr)*])*
+                )))*
                 .main();
         }
     }
 }
+
+/// What most tests need, for `use libtest2::prelude::*;`
+pub mod prelude {
+    pub use crate::bail;
+    pub use crate::ensure;
+    pub use crate::RunError;
+    pub use crate::RunResult;
+    pub use crate::State;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trial_source_is_caller() {
+        let line = line!() as usize + 1;
+        let trial = Trial::test("foo", |_| Ok(()));
+        match trial.source() {
+            Some(Source::Rust {
+                source_file,
+                start_line,
+                ..
+            }) => {
+                assert_eq!(source_file, std::path::Path::new(file!()));
+                assert_eq!(*start_line, line);
+            }
+            source => panic!("unexpected source: {source:?}"),
+        }
+    }
+}
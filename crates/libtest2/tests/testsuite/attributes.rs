@@ -0,0 +1,106 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(
+    passes,
+    #[ignore] ignored,
+    #[ignore = "slow"] ignored_for,
+    #[should_panic] panics,
+    #[should_panic(expected = "answer")] panics_expected,
+    #[should_panic(expected = "answer")] panics_unexpected,
+    #[should_panic] no_panic,
+);
+
+fn passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn ignored(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn ignored_for(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn panics(_state: &libtest2::State) -> libtest2::RunResult {
+    panic!("boom")
+}
+
+fn panics_expected(_state: &libtest2::State) -> libtest2::RunResult {
+    panic!("wrong answer")
+}
+
+fn panics_unexpected(_state: &libtest2::State) -> libtest2::RunResult {
+    panic!("boom")
+}
+
+fn no_panic(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn normal() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 7 tests
+test ignored           ... ignored
+test ignored_for       ... ignored, slow
+test no_panic          ... FAILED
+test panics            ... ok
+test panics_expected   ... ok
+test panics_unexpected ... FAILED
+test passes            ... ok
+
+failures:
+
+---- no_panic ----
+test did not panic as expected
+
+---- panics_unexpected ----
+panic did not contain expected string
+      panic message: `"boom"`,
+ expected substring: `"answer"`
+
+
+failures:
+    no_panic
+    panics_unexpected
+
+test result: FAILED. 3 passed; 2 failed; 2 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn include_ignored() {
+    test_cmd()
+        .args(["--test-threads", "1", "--include-ignored", "ignored"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test ignored     ... ok
+test ignored_for ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 5 filtered out; finished in [..]s
+
+"#,
+        );
+}
@@ -0,0 +1,150 @@
+static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+    once_cell::sync::Lazy::new(|| {
+        let package_root = crate::util::new_test(
+            r#"
+libtest2::libtest2_main!(cat, dog, fox);
+
+fn check(name: &str) -> libtest2::RunResult {
+    let failing = std::env::var("FAILING").unwrap_or_default();
+    if failing.split(',').any(|f| f == name) {
+        return Err(libtest2::RunError::fail("broken"));
+    }
+    Ok(())
+}
+
+fn cat(_state: &libtest2::State) -> libtest2::RunResult {
+    check("cat")
+}
+
+fn dog(_state: &libtest2::State) -> libtest2::RunResult {
+    check("dog")
+}
+
+fn fox(_state: &libtest2::State) -> libtest2::RunResult {
+    check("fox")
+}
+"#,
+            false,
+        );
+        let bin = crate::util::compile_test(&package_root);
+        (bin, package_root)
+    });
+
+fn test_cmd() -> snapbox::cmd::Command {
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn results(name: &str) -> std::path::PathBuf {
+    BIN.1.join(name)
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--save-results", "results.json"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--save-results` requires `-Zunstable-options`
+"#,
+        );
+    test_cmd()
+        .args(["--baseline", "results.json"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--baseline` requires `-Zunstable-options`
+"#,
+        );
+}
+
+#[test]
+fn save_results() {
+    let path = results("save_results.json");
+    test_cmd()
+        .env("FAILING", "cat")
+        .args(["-Zunstable-options", "--save-results"])
+        .arg(&path)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101);
+    snapbox::assert_matches(
+        r#"{
+  "cases": {
+    "cat": {
+      "status": "failed",
+      "elapsed_s": "[..]"
+    },
+    "dog": {
+      "status": "ok",
+      "elapsed_s": "[..]"
+    },
+    "fox": {
+      "status": "ok",
+      "elapsed_s": "[..]"
+    }
+  }
+}"#,
+        std::fs::read_to_string(&path).unwrap(),
+    );
+}
+
+#[test]
+fn compare_to_baseline() {
+    let path = results("compare_to_baseline.json");
+    test_cmd()
+        .env("FAILING", "cat,fox")
+        .args(["-Zunstable-options", "--save-results"])
+        .arg(&path)
+        .assert()
+        .code(101);
+    test_cmd()
+        .env("FAILING", "dog,fox")
+        .args(["-Zunstable-options", "--baseline"])
+        .arg(&path)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 3 tests
+test cat ... ok
+test dog ... FAILED
+test fox ... FAILED
+
+failures:
+
+---- dog ----
+broken
+
+---- fox ----
+broken
+
+
+failures:
+    dog (NEW FAILURE)
+    fox
+
+fixed:
+    cat (FIXED)
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn missing_baseline() {
+    test_cmd()
+        .args(["-Zunstable-options", "--baseline", "missing.json"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"could not read baseline `missing.json`: [..]
+"#,
+        );
+}
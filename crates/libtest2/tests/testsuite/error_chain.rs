@@ -0,0 +1,78 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(config, nested);
+
+#[derive(Debug)]
+struct Context(&'static str, Box<dyn std::error::Error + Send + Sync>);
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Context {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.1.as_ref())
+    }
+}
+
+fn config(_state: &libtest2::State) -> Result<(), Context> {
+    let err = std::io::Error::other("permission denied");
+    Err(Context("could not load config", err.into()))
+}
+
+fn nested(_state: &libtest2::State) -> Result<(), Context> {
+    let err = std::io::Error::other("permission denied");
+    let err = Context("could not open `settings.toml`", err.into());
+    Err(Context("could not load config", err.into()))
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn sources_are_shown() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+test config ... FAILED
+test nested ... FAILED
+
+failures:
+
+---- config ----
+could not load config
+
+Caused by:
+    permission denied
+
+---- nested ----
+could not load config
+
+Caused by:
+    0: could not open `settings.toml`
+    1: permission denied
+
+
+failures:
+    config
+    nested
+
+test result: FAILED. 0 passed; 2 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
@@ -0,0 +1,238 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(passes, fails, missing_fixture);
+
+fn passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn fails(_state: &libtest2::State) -> libtest2::RunResult {
+    Err(libtest2::RunError::fail("wrong answer"))
+}
+
+fn missing_fixture(_state: &libtest2::State) -> libtest2::RunResult {
+    Err(libtest2::RunError::error("fixture.txt not found"))
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        101,
+        r#"
+running 3 tests
+test fails           ... FAILED
+test missing_fixture ... ERRORED
+test passes          ... ok
+
+failures:
+
+---- fails ----
+wrong answer
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    fails
+    missing_fixture
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 1 errored; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+failures:
+
+---- fails ----
+wrong answer
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    fails
+    missing_fixture
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 1 errored; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn only_errored() {
+    check(
+        &["missing_fixture"],
+        101,
+        r#"
+running 1 test
+test missing_fixture ... ERRORED
+
+failures:
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    missing_fixture
+
+test result: FAILED. 0 passed; 0 failed; 0 ignored; 1 errored; 2 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 1 test
+test missing_fixture ... ERRORED
+
+failures:
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    missing_fixture
+
+test result: FAILED. 0 passed; 0 failed; 0 ignored; 1 errored; 2 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn terse() {
+    test_cmd()
+        .args(["--quiet", "--test-threads", "1", "missing_fixture"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 1 test
+E
+failures:
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    missing_fixture
+
+test result: FAILED. 0 passed; 0 failed; 0 ignored; 1 errored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn result_line() {
+    test_cmd()
+        .env("LIBTEST2_RESULT_LINE", "1")
+        .args(["--quiet", "--test-threads", "1", "--skip", "fails"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+E.
+failures:
+
+---- missing_fixture ----
+fixture.txt not found
+
+
+failures:
+    missing_fixture
+
+test result: FAILED. 1 passed; 0 failed; 0 ignored; 1 errored; 1 filtered out; finished in [..]s
+
+::libtest2:result passed=1 failed=0 ignored=0 skipped=0 errored=1 filtered_out=1
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--test-threads=1",
+            "missing_fixture",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"fails","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"missing_fixture","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"passes","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":2,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"missing_fixture","suite":"[..]"}
+{"event":"case-complete","name":"missing_fixture","mode":"test","status":"errored","message":"fixture.txt not found","elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "junit")]
+fn junit() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=junit",
+            "--test-threads=1",
+            "f",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+<testsuite name="test" package="test" id="0" tests="2" errors="1" failures="1" skipped="0" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="crate" name="fails" time="[..]s">
+<failure type="assert"><![CDATA[wrong answer]]></failure>
+</testcase>
+<testcase classname="crate" name="missing_fixture" time="[..]s">
+<error type="error"><![CDATA[fixture.txt not found]]></error>
+</testcase>
+<system-out/>
+<system-err/>
+</testsuite>
+</testsuites>
+"#,
+        );
+}
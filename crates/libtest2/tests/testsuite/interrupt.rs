@@ -0,0 +1,100 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(a_passes, b_interrupts, c_not_run, d_killed);
+
+fn a_passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn b_interrupts(_state: &libtest2::State) -> libtest2::RunResult {
+    extern "C" {
+        fn raise(signum: std::os::raw::c_int) -> std::os::raw::c_int;
+    }
+    const SIGINT: std::os::raw::c_int = 2;
+    unsafe {
+        raise(SIGINT);
+    }
+    Ok(())
+}
+
+fn c_not_run(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn d_killed(_state: &libtest2::State) -> libtest2::RunResult {
+    extern "C" {
+        fn raise(signum: std::os::raw::c_int) -> std::os::raw::c_int;
+    }
+    const SIGINT: std::os::raw::c_int = 2;
+    unsafe {
+        raise(SIGINT);
+        raise(SIGINT);
+    }
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--graceful-interrupt", "a_passes"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--graceful-interrupt` requires `-Zunstable-options`
+"#,
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn partial_results() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--graceful-interrupt",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(130)
+        .stdout_matches(
+            r#"
+running 4 tests
+test a_passes     ... ok
+test b_interrupts ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        )
+        .stderr_matches(
+            r#"error: interrupted, remaining tests were not run
+"#,
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn second_interrupt_exits() {
+    test_cmd()
+        .args(["-Zunstable-options", "--graceful-interrupt", "d_killed"])
+        .assert()
+        .interrupted()
+        .stdout_matches(
+            r#"
+running 1 test
+test d_killed ... "#,
+        );
+}
@@ -0,0 +1,179 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(passes, flaky);
+
+fn passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn flaky(_state: &libtest2::State) -> libtest2::RunResult {
+    static RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    if run == 3 {
+        return Err(libtest2::RunError::fail(format!("failed on run {run}")));
+    }
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--iterations", "5"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--iterations` requires `-Zunstable-options`
+"#,
+        );
+}
+
+#[test]
+fn zero() {
+    test_cmd()
+        .args(["-Zunstable-options", "--iterations", "0"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--iterations` must be a positive integer
+"#,
+        );
+}
+
+#[test]
+fn all_pass() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--iterations",
+            "5",
+            "--test-threads",
+            "1",
+            "passes",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test passes ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn any_failure_fails() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--iterations",
+            "5",
+            "--test-threads",
+            "1",
+            "flaky",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 1 test
+test flaky ... FAILED
+
+failures:
+
+---- flaky ----
+failed on run 3
+
+
+failures:
+    flaky
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn report_time() {
+    test_cmd()
+        .args(["--report-time", "--test-threads", "1", "passes"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test passes ... ok <[..]s>
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn report_time_aggregate() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--iterations",
+            "5",
+            "--report-time",
+            "--test-threads",
+            "1",
+            "passes",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test passes ... ok <min [..]s, median [..]s, max [..]s>
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--iterations",
+            "5",
+            "--format=json",
+            "--test-threads=1",
+            "passes",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"flaky","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"passes","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":1,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"passes","suite":"[..]"}
+{"event":"case-complete","name":"passes","mode":"test","status":null,"message":null,"elapsed_s":"[..]","timings":{"iterations":5,"min_s":"[..]","median_s":"[..]","max_s":"[..]"},"suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
@@ -0,0 +1,113 @@
+static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+    once_cell::sync::Lazy::new(|| {
+        let package_root = crate::util::new_test(
+            r##"
+fn main() {
+    use libtest2::Trial;
+    use libtest2::RunError;
+    libtest2::Harness::with_env()
+        .cases(vec![
+            Trial::test("a<b>::c&\"d'", |_| Ok(())),
+            Trial::test("e<f>&\"g'", |_| Err(RunError::fail("<oops> & \"]]>\" 'uh oh'"))),
+        ])
+        .main();
+}
+"##,
+            false,
+        );
+        let bin = crate::util::compile_test(&package_root);
+        (bin, package_root)
+    });
+
+fn test_cmd() -> snapbox::cmd::Command {
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn valid_xml() {
+    let output = test_cmd()
+        .args(["-Zunstable-options", "--format=junit"])
+        .assert()
+        .code(101)
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+
+    let doc = roxmltree::Document::parse(&output).unwrap_or_else(|e| panic!("{e}:\n{output}"));
+    let cases = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("testcase"))
+        .map(|n| {
+            (
+                n.attribute("classname").unwrap().to_owned(),
+                n.attribute("name").unwrap().to_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        cases,
+        [
+            (r#"a<b>"#.to_owned(), r#"c&"d'"#.to_owned()),
+            ("crate".to_owned(), r#"e<f>&"g'"#.to_owned()),
+        ]
+    );
+    let failure = doc
+        .descendants()
+        .find(|n| n.has_tag_name("failure"))
+        .unwrap()
+        .text()
+        .unwrap();
+    assert_eq!(failure, r#"<oops> & "]]>" 'uh oh'"#);
+}
+
+#[test]
+fn root_classname() {
+    let output = test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=junit",
+            "--junit-classname=ui",
+        ])
+        .assert()
+        .code(101)
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+
+    let doc = roxmltree::Document::parse(&output).unwrap_or_else(|e| panic!("{e}:\n{output}"));
+    let classnames = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("testcase"))
+        .map(|n| n.attribute("classname").unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(classnames, [r#"a<b>"#, "ui"]);
+}
+
+#[test]
+fn junit_path() {
+    let path = BIN.1.join("report.xml");
+    test_cmd()
+        .args(["-Zunstable-options", "--test-threads=1", "--junit-path"])
+        .arg(&path)
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 2 tests
+test a<b>::c&"d' ... ok
+test e<f>&"g'    ... FAILED
+...
+"#,
+        );
+    let output = std::fs::read_to_string(&path).unwrap();
+
+    let doc = roxmltree::Document::parse(&output).unwrap_or_else(|e| panic!("{e}:\n{output}"));
+    let names = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("testcase"))
+        .map(|n| n.attribute("name").unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [r#"c&"d'"#, r#"e<f>&"g'"#]);
+}
@@ -0,0 +1,160 @@
+static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+    once_cell::sync::Lazy::new(|| {
+        let package_root = crate::util::new_test(
+            r#"
+libtest2::libtest2_main!(passes, fails);
+
+fn passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn fails(_state: &libtest2::State) -> libtest2::RunResult {
+    Err(libtest2::RunError::fail("wrong answer"))
+}
+"#,
+            false,
+        );
+        let bin = crate::util::compile_test(&package_root);
+        (bin, package_root)
+    });
+
+fn test_cmd() -> snapbox::cmd::Command {
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn logfile(name: &str) -> std::path::PathBuf {
+    BIN.1.join(name)
+}
+
+#[test]
+fn plain() {
+    let path = logfile("plain.log");
+    test_cmd()
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--test-threads", "1", "passes"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test passes ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        );
+    snapbox::assert_matches(
+        r#"
+running 1 test
+test passes ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 1 filtered out; finished in [..]s
+
+"#,
+        std::fs::read_to_string(&path).unwrap(),
+    );
+}
+
+#[test]
+fn color_always() {
+    let path = logfile("color_always.log");
+    test_cmd()
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--color=always", "--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(log.contains("\u{1b}["), "colors were stripped:\n{log}");
+}
+
+#[test]
+fn color_auto() {
+    let path = logfile("color_auto.log");
+    test_cmd()
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(!log.contains("\u{1b}["), "colors were kept:\n{log}");
+}
+
+#[test]
+fn no_color() {
+    let path = logfile("no_color.log");
+    test_cmd()
+        .arg("--logfile")
+        .arg(&path)
+        .args([
+            "--color=always",
+            "--no-color",
+            "--test-threads",
+            "1",
+            "fails",
+        ])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(!log.contains("\u{1b}["), "colors were kept:\n{log}");
+}
+
+#[test]
+fn cargo_term_color() {
+    let path = logfile("cargo_term_color.log");
+    test_cmd()
+        .env("CARGO_TERM_COLOR", "always")
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(log.contains("\u{1b}["), "colors were stripped:\n{log}");
+}
+
+#[test]
+fn color_over_cargo_term_color() {
+    let path = logfile("color_over_cargo_term_color.log");
+    test_cmd()
+        .env("CARGO_TERM_COLOR", "always")
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--color=never", "--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(!log.contains("\u{1b}["), "colors were kept:\n{log}");
+}
+
+#[test]
+fn clicolor_force() {
+    let path = logfile("clicolor_force.log");
+    test_cmd()
+        .env("CLICOLOR_FORCE", "1")
+        .env_remove("NO_COLOR")
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(log.contains("\u{1b}["), "colors were stripped:\n{log}");
+}
+
+#[test]
+fn no_color_over_clicolor_force() {
+    let path = logfile("no_color_over_clicolor_force.log");
+    test_cmd()
+        .env("CLICOLOR_FORCE", "1")
+        .env("NO_COLOR", "1")
+        .arg("--logfile")
+        .arg(&path)
+        .args(["--test-threads", "1", "fails"])
+        .assert()
+        .code(101);
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(!log.contains("\u{1b}["), "colors were kept:\n{log}");
+}
@@ -0,0 +1,112 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+use libtest2::prelude::*;
+
+libtest2::libtest2_main!(
+    bails,
+    ensures,
+    ensures_with_message,
+    passes,
+    io_error,
+    state_fails,
+    state_asserts,
+);
+
+fn bails(_state: &State) -> RunResult {
+    let answer = 41;
+    bail!("wrong answer: {answer}");
+}
+
+fn ensures(_state: &State) -> RunResult {
+    let items: Vec<u8> = vec![];
+    ensure!(!items.is_empty());
+    Ok(())
+}
+
+fn ensures_with_message(_state: &State) -> RunResult {
+    let (x, y) = (1, 2);
+    ensure!(x == y, "mismatch: {x} != {y}");
+    Ok(())
+}
+
+fn passes(_state: &State) -> RunResult {
+    ensure!(1 + 1 == 2);
+    Ok(())
+}
+
+fn io_error(_state: &State) -> std::io::Result<()> {
+    Err(std::io::Error::other("disk full"))
+}
+
+fn state_fails(state: &State) -> RunResult {
+    state.fail("gave up")
+}
+
+fn state_asserts(state: &State) -> RunResult {
+    let (x, y) = (1, 2);
+    state.assert(x < y, || "unreachable")?;
+    state.assert(x == y, || format!("mismatch: {x} != {y}"))
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn failures_report_location() {
+    test_cmd()
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 7 tests
+test bails                ... FAILED
+test ensures              ... FAILED
+test ensures_with_message ... FAILED
+test io_error             ... FAILED
+test passes               ... ok
+test state_asserts        ... FAILED
+test state_fails          ... FAILED
+
+failures:
+
+---- bails ----
+test.rs:16: wrong answer: 41
+
+---- ensures ----
+test.rs:21: condition failed: `!items.is_empty()`
+
+---- ensures_with_message ----
+test.rs:27: mismatch: 1 != 2
+
+---- io_error ----
+disk full
+
+---- state_asserts ----
+test.rs:47: mismatch: 1 != 2
+
+---- state_fails ----
+test.rs:41: gave up
+
+
+failures:
+    bails
+    ensures
+    ensures_with_message
+    io_error
+    state_asserts
+    state_fails
+
+test result: FAILED. 1 passed; 6 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
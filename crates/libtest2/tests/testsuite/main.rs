@@ -1,6 +1,19 @@
 mod all_passing;
+mod attributes;
+mod baseline;
+mod error_chain;
+mod errored;
+mod interrupt;
+mod iterations;
+#[cfg(feature = "junit")]
+mod junit;
+mod logfile;
+mod macros;
 mod mixed_bag;
 mod panic;
+mod partition;
+mod repeat;
+mod skip;
 mod util;
 
 pub use util::*;
@@ -47,7 +47,7 @@ fn bear(state: &libtest2::State) -> libtest2::RunResult {
             let bin = crate::util::compile_test(&package_root);
             (bin, package_root)
         });
-    snapbox::cmd::Command::new(&BIN.0).current_dir(&BIN.1)
+    crate::util::test_bin(&BIN.0, &BIN.1)
 }
 
 fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
@@ -71,14 +71,14 @@ fn normal() {
         101,
         r#"
 running 8 tests
-test bear  ... ignored
-test bunny ... ignored
+test bear  ... ignored, fails
+test bunny ... ignored, fails
 test cat   ... ok
 test dog   ... FAILED
-test fly   ... ignored
+test fly   ... ignored, fails
 test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
+test frog  ... ignored, slow
+test owl   ... ignored, fails
 
 failures:
 
@@ -118,14 +118,14 @@ fn test_mode() {
         101,
         r#"
 running 8 tests
-test bear  ... ignored
-test bunny ... ignored
+test bear  ... ignored, fails
+test bunny ... ignored, fails
 test cat   ... ok
 test dog   ... FAILED
-test fly   ... ignored
+test fly   ... ignored, fails
 test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
+test frog  ... ignored, slow
+test owl   ... ignored, fails
 
 failures:
 
@@ -162,44 +162,17 @@ test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in
 fn bench_mode() {
     check(
         &["--bench"],
-        101,
+        0,
         r#"
-running 8 tests
-test bear  ... ignored
-test bunny ... ignored
-test cat   ... ok
-test dog   ... FAILED
-test fly   ... ignored
-test fox   ... ok
-test frog  ... ignored
-test owl   ... ignored
-
-failures:
+running 0 tests
 
----- dog ----
-was not a good boy
-
-
-failures:
-    dog
-
-test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 8 tests
-...
-
-failures:
-
----- dog ----
-was not a good boy
-
+running 0 tests
 
-failures:
-    dog
-
-test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
     )
@@ -238,32 +211,171 @@ owl: test
 }
 
 #[test]
-fn list_ignored() {
+fn dry_run() {
     check(
-        &["--list", "--ignored"],
+        &[
+            "-Zunstable-options",
+            "--dry-run",
+            "--exact",
+            "--skip",
+            "dog",
+            "cat",
+            "dog",
+            "fox",
+        ],
         0,
-        r#"bear: test
-bunny: test
-cat: test
-dog: test
-fly: test
-fox: test
-frog: test
-owl: test
+        r#"bear (filtered)
+bunny (filtered)
+cat (will run)
+dog (filtered)
+fly (filtered)
+fox (will run)
+frog (filtered)
+owl (filtered)
+
+2 will run; 0 ignored; 6 filtered out
+"#,
+        r#"bear (filtered)
+bunny (filtered)
+cat (will run)
+dog (filtered)
+fly (filtered)
+fox (will run)
+frog (filtered)
+owl (filtered)
+
+2 will run; 0 ignored; 6 filtered out
+"#,
+    )
+}
 
-8 tests
+#[test]
+fn nocapture_needs_json() {
+    test_cmd()
+        .args(["--nocapture"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"error: `--nocapture` is only supported with `--format=json` and `--run-in-subprocess`
+"#,
+        );
+}
+
+#[test]
+fn nocapture_list() {
+    test_cmd()
+        .args(["--nocapture", "--list", "--exact", "cat"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"cat: test
+
+1 tests
 
 "#,
-        r#"bear: test
-bunny: test
-cat: test
-dog: test
-fly: test
-fox: test
-frog: test
-owl: test
+        );
+}
 
-8 tests
+#[test]
+fn filter_all() {
+    test_cmd()
+        .args(["-Zunstable-options", "--dry-run", "--filter-all", "o", "f"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"bear (filtered)
+bunny (filtered)
+cat (filtered)
+dog (filtered)
+fly (filtered)
+fox (will run)
+frog (will run)
+owl (filtered)
+
+2 will run; 0 ignored; 6 filtered out
+"#,
+        )
+        .stderr_matches("");
+}
+
+#[test]
+fn filter_file_stdin() {
+    test_cmd()
+        .args(["-Zunstable-options", "--dry-run", "--filter-file", "-"])
+        .stdin("cat\nfo\n\ndog\n")
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"bear (filtered)
+bunny (filtered)
+cat (will run)
+dog (will run)
+fly (filtered)
+fox (filtered)
+frog (filtered)
+owl (filtered)
+
+2 will run; 0 ignored; 6 filtered out
+"#,
+        )
+        .stderr_matches("");
+}
+
+#[test]
+fn filter_file_with_filter() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--dry-run",
+            "--filter-file",
+            "-",
+            "fo",
+        ])
+        .stdin("cat\n")
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"bear (filtered)
+bunny (filtered)
+cat (will run)
+dog (filtered)
+fly (filtered)
+fox (will run)
+frog (filtered)
+owl (filtered)
+
+2 will run; 0 ignored; 6 filtered out
+"#,
+        )
+        .stderr_matches("");
+}
+
+#[test]
+fn filter_file_empty() {
+    test_cmd()
+        .args(["-Zunstable-options", "--filter-file", "-"])
+        .stdin("\n")
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"error: could not read filter file `-`: no test names
+"#,
+        );
+}
+
+#[test]
+fn list_ignored() {
+    check(
+        &["--list", "--ignored"],
+        0,
+        r#"
+0 tests
+
+"#,
+        r#"
+0 tests
 
 "#,
     );
@@ -296,7 +408,7 @@ fn filter_c() {
         0,
         r#"
 running 2 tests
-test bear ... ignored
+test bear ... ignored, fails
 test cat  ... ok
 
 test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
@@ -319,7 +431,7 @@ fn filter_o_test() {
         0,
         r#"
 running 2 tests
-test bear ... ignored
+test bear ... ignored, fails
 test cat  ... ok
 
 test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
@@ -390,51 +502,20 @@ test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in
 fn filter_o_test_ignored() {
     check(
         &["--test", "--ignored", "o"],
-        101,
+        0,
         r#"
-running 4 tests
-test dog  ... FAILED
-test fox  ... ok
-test frog ... ok
-test owl  ... FAILED
-
-failures:
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
+running 0 tests
 
-failures:
-    dog
-    owl
-
-test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 4 tests
-...
-
-failures:
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
-
+running 0 tests
 
-failures:
-    dog
-    owl
-
-test result: FAILED. 2 passed; 2 failed; 0 ignored; 4 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
-    )
+    );
 }
 
 #[test]
@@ -512,109 +593,60 @@ test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in
 fn normal_ignored() {
     check(
         &["--ignored"],
-        101,
+        0,
         r#"
-running 8 tests
-test bear  ... FAILED
-test bunny ... FAILED
-test cat   ... ok
-test dog   ... FAILED
-test fly   ... ok
-test fox   ... ok
-test frog  ... ok
-test owl   ... FAILED
-
-failures:
-
----- bear ----
-no honey
-
----- bunny ----
-jumped too high
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
+running 0 tests
 
-
-failures:
-    bear
-    bunny
-    dog
-    owl
-
-test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
         r#"
-running 8 tests
-...
-
-failures:
-
----- bear ----
-no honey
-
----- bunny ----
-jumped too high
-
----- dog ----
-was not a good boy
-
----- owl ----
-broke neck
+running 0 tests
 
-
-failures:
-    bear
-    bunny
-    dog
-    owl
-
-test result: FAILED. 4 passed; 4 failed; 0 ignored; 0 filtered out; finished in [..]s
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
 "#,
-    )
+    );
 }
 
 #[test]
 fn lots_of_flags() {
     check(
         &["--ignored", "--skip", "g", "--test", "o"],
-        101,
+        0,
         r#"
-running 2 tests
-test fox ... ok
-test owl ... FAILED
+running 0 tests
 
-failures:
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
----- owl ----
-broke neck
+"#,
+        r#"
+running 0 tests
 
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
 
-failures:
-    owl
+"#,
+    );
+}
+
+#[test]
+fn force_run_in_process() {
+    check(
+        &["-Zunstable-options", "--force-run-in-process", "a"],
+        0,
+        r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
 
-test result: FAILED. 1 passed; 1 failed; 0 ignored; 6 filtered out; finished in [..]s
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
 
 "#,
         r#"
 running 2 tests
 ...
 
-failures:
-
----- owl ----
-broke neck
-
-
-failures:
-    owl
-
-test result: FAILED. 1 passed; 1 failed; 0 ignored; 6 filtered out; finished in [..]s
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
 
 "#,
     )
@@ -626,27 +658,190 @@ fn list_json() {
     check(
         &["-Zunstable-options", "--format=json", "--list", "a"],
         0,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-"#,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+"#,
+    )
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn list_json_pretty() {
+    check(
+        &["-Zunstable-options", "--format=json-pretty", "--list", "a"],
+        0,
+        r#"{
+  "event": "discover-start",
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "bear",
+  "mode": "test",
+  "run": true,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "bunny",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "cat",
+  "mode": "test",
+  "run": true,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "dog",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "fly",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "fox",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "frog",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "owl",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-complete",
+  "elapsed_s": "[..]",
+  "seed": null,
+  "num_to_run": 2,
+  "num_filtered_out": 6,
+  "suite": "[..]"
+}
+"#,
+        r#"{
+  "event": "discover-start",
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "bear",
+  "mode": "test",
+  "run": true,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "bunny",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "cat",
+  "mode": "test",
+  "run": true,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "dog",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "fly",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "fox",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "frog",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-case",
+  "name": "owl",
+  "mode": "test",
+  "run": false,
+  "ignored": false,
+  "suite": "[..]"
+}
+{
+  "event": "discover-complete",
+  "elapsed_s": "[..]",
+  "seed": null,
+  "num_to_run": 2,
+  "num_filtered_out": 6,
+  "suite": "[..]"
+}
 "#,
     )
 }
@@ -657,43 +852,68 @@ fn test_json() {
     check(
         &["-Zunstable-options", "--format=json", "a"],
         0,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-{"event":"suite-start"}
-{"event":"case-start","name":"bear"}
-{"event":"case-complete","name":"bear","mode":"test","status":"ignored","message":"fails","elapsed_s":"[..]"}
-{"event":"case-start","name":"cat"}
-{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]"}
-{"event":"suite-complete","elapsed_s":"[..]"}
-"#,
-        r#"{"event":"discover-start"}
-{"event":"discover-case","name":"bear","mode":"test","run":true}
-{"event":"discover-case","name":"bunny","mode":"test","run":false}
-{"event":"discover-case","name":"cat","mode":"test","run":true}
-{"event":"discover-case","name":"dog","mode":"test","run":false}
-{"event":"discover-case","name":"fly","mode":"test","run":false}
-{"event":"discover-case","name":"fox","mode":"test","run":false}
-{"event":"discover-case","name":"frog","mode":"test","run":false}
-{"event":"discover-case","name":"owl","mode":"test","run":false}
-{"event":"discover-complete","elapsed_s":"[..]","seed":null}
-{"event":"suite-start"}
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"bear","suite":"[..]"}
+{"event":"case-complete","name":"bear","mode":"test","status":"ignored","message":"fails","elapsed_s":"[..]","suite":"[..]"}
+{"event":"case-start","name":"cat","suite":"[..]"}
+{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
 [..]
 [..]
 [..]
 [..]
-{"event":"suite-complete","elapsed_s":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
 "#,
     )
 }
 
+#[test]
+#[cfg(feature = "json")]
+fn test_json_timeline() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--quiet",
+            "--timeline",
+            "--test-threads=1",
+            "cat",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":7,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"cat","at_s":"[..]","suite":"[..]"}
+{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]","at_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
+
 #[test]
 #[cfg(feature = "junit")]
 fn list_junit() {
@@ -723,8 +943,11 @@ fn test_junit() {
         0,
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <testsuites>
-<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" >
-<testcase classname="crate" name="cat" time="0.000s"/>
+<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="crate" name="bear" time="0.000s">
+<skipped message="fails"/>
+</testcase>
+...
 <system-out/>
 <system-err/>
 </testsuite>
@@ -732,8 +955,8 @@ fn test_junit() {
 "#,
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <testsuites>
-<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" >
-<testcase classname="crate" name="cat" time="0.000s"/>
+<testsuite name="test" package="test" id="0" tests="2" errors="0" failures="0" skipped="1" time="[..]" timestamp="[..]" hostname="[..]" >
+...
 <system-out/>
 <system-err/>
 </testsuite>
@@ -785,26 +1008,26 @@ fn shuffle() {
     check(
         &["-Zunstable-options", "--list", "--shuffle-seed=1"],
         0,
-        r#"fox: test
-cat: test
-fly: test
+        r#"owl: test
 bear: test
-owl: test
+fox: test
+dog: test
 frog: test
+fly: test
+cat: test
 bunny: test
-dog: test
 
 8 tests
 
 "#,
-        r#"fox: test
-cat: test
-fly: test
+        r#"owl: test
 bear: test
-owl: test
+fox: test
+dog: test
 frog: test
+fly: test
+cat: test
 bunny: test
-dog: test
 
 8 tests
 
@@ -813,29 +1036,484 @@ dog: test
     check(
         &["-Zunstable-options", "--list", "--shuffle-seed=2"],
         0,
-        r#"owl: test
+        r#"bunny: test
+fly: test
 dog: test
-fox: test
 frog: test
-bear: test
-fly: test
-bunny: test
 cat: test
+bear: test
+fox: test
+owl: test
 
 8 tests
 
 "#,
-        r#"owl: test
+        r#"bunny: test
+fly: test
 dog: test
-fox: test
 frog: test
-bear: test
-fly: test
-bunny: test
 cat: test
+bear: test
+fox: test
+owl: test
 
 8 tests
 
 "#,
     );
 }
+
+#[test]
+fn fail_on_empty() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--fail-on-empty",
+            "--test-threads",
+            "1",
+            "cow",
+            "pig",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
+
+"#,
+        )
+        .stderr_matches(
+            r#"error: no tests matched filter `cow`, `pig`
+"#,
+        );
+}
+
+#[test]
+fn fail_on_empty_list() {
+    test_cmd()
+        .args(["-Zunstable-options", "--fail-on-empty", "--list", "cow"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+0 tests
+
+"#,
+        )
+        .stderr_matches(
+            r#"error: no tests matched filter `cow`
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn fail_on_empty_list_json() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--fail-on-empty",
+            "--list",
+            "cow",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":0,"num_filtered_out":8,"suite":"[..]"}
+"#,
+        );
+}
+
+#[test]
+fn fail_on_empty_with_tests() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--fail-on-empty",
+            "--test-threads",
+            "1",
+            "cat",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test cat ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 7 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn empty() {
+    test_cmd()
+        .args(["--test-threads", "1", "cow"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 8 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn progress_without_terminal() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--progress",
+            "--test-threads",
+            "1",
+            "a",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        )
+        .stderr_matches("");
+}
+
+#[test]
+fn unknown_flag_suggestion() {
+    test_cmd()
+        .args(["--formta=json"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"unrecognized `--formta` flag, did you mean `--format`?
+"#,
+        );
+}
+
+#[test]
+fn unknown_unstable_feature() {
+    test_cmd()
+        .args(["-Ztypo-options", "--test-threads", "1", "a"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        )
+        .stderr_matches(
+            r#"warning: unrecognized `-Z typo-options` feature
+"#,
+        );
+}
+
+#[test]
+fn escaped_filters() {
+    test_cmd()
+        .args(["--test-threads", "1", "--", "a"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn escaped_flag_is_filter() {
+    test_cmd()
+        .args(["--test-threads", "1", "--", "--skip", "a"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn args_from_env() {
+    test_cmd()
+        .env("LIBTEST2_ARGS", "--test-threads 4  a")
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn args_from_env_unterminated() {
+    test_cmd()
+        .env("LIBTEST2_ARGS", "--skip 'a")
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"error: invalid `LIBTEST2_ARGS`: unterminated `'`
+"#,
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn args_from_env_not_utf8() {
+    use std::os::unix::ffi::OsStrExt as _;
+    test_cmd()
+        .env("LIBTEST2_ARGS", std::ffi::OsStr::from_bytes(b"--skip \xff"))
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"error: `LIBTEST2_ARGS` is not valid UTF-8
+"#,
+        );
+}
+
+#[test]
+fn fail_fast() {
+    test_cmd()
+        .args(["--fail-fast", "--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 8 tests
+test bear  ... ignored, fails
+test bunny ... ignored, fails
+test cat   ... ok
+test dog   ... FAILED
+
+failures:
+
+---- dog ----
+was not a good boy
+
+
+failures:
+    dog
+
+aborted after first failure; 4 tests not run
+
+test result: FAILED. 1 passed; 1 failed; 2 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn no_fail_fast() {
+    test_cmd()
+        .args(["--fail-fast", "--no-fail-fast", "--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 8 tests
+test bear  ... ignored, fails
+test bunny ... ignored, fails
+test cat   ... ok
+test dog   ... FAILED
+test fly   ... ignored, fails
+test fox   ... ok
+test frog  ... ignored, slow
+test owl   ... ignored, fails
+
+failures:
+
+---- dog ----
+was not a good boy
+
+
+failures:
+    dog
+
+test result: FAILED. 2 passed; 1 failed; 5 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_stderr_pretty() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--json-stderr-pretty",
+            "--test-threads=1",
+            "a",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"bear","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"bunny","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"cat","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"dog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fly","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"fox","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"frog","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"owl","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":2,"num_filtered_out":6,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"bear","suite":"[..]"}
+{"event":"case-complete","name":"bear","mode":"test","status":"ignored","message":"fails","elapsed_s":"[..]","suite":"[..]"}
+{"event":"case-start","name":"cat","suite":"[..]"}
+{"event":"case-complete","name":"cat","mode":"test","status":null,"message":null,"elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        )
+        .stderr_matches(
+            r#"
+running 2 tests
+test bear ... ignored, fails
+test cat  ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "junit")]
+fn test_junit_failure() {
+    check(
+        &["-Zunstable-options", "--format=junit", "dog"],
+        101,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+<testsuite name="test" package="test" id="0" tests="1" errors="0" failures="1" skipped="0" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="crate" name="dog" time="[..]s">
+<failure type="assert"><![CDATA[was not a good boy]]></failure>
+</testcase>
+<system-out/>
+<system-err/>
+</testsuite>
+</testsuites>
+"#,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+<testsuite name="test" package="test" id="0" tests="1" errors="0" failures="1" skipped="0" time="[..]" timestamp="[..]" hostname="[..]" >
+<testcase classname="crate" name="dog" time="[..]s">
+<failure type="assert"><![CDATA[was not a good boy]]></failure>
+</testcase>
+<system-out/>
+<system-err/>
+</testsuite>
+</testsuites>
+"#,
+    )
+}
+
+#[test]
+fn test_threads_percent() {
+    test_cmd()
+        .args(["--test-threads=50%", "a"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+...
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn test_threads_zero() {
+    test_cmd()
+        .args(["--test-threads=0", "a"])
+        .env("RUST_TEST_THREADS", "1")
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 2 tests
+...
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 6 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn test_threads_zero_percent() {
+    test_cmd()
+        .args(["--test-threads=0%"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--test-threads` percentage must be a positive integer
+"#,
+        );
+}
+
+#[test]
+fn invalid_shuffle_seed_env() {
+    test_cmd()
+        .args(["-Zunstable-options"])
+        .env("RUST_TEST_SHUFFLE_SEED", "abc")
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"RUST_TEST_SHUFFLE_SEED is `abc`, should be a number.
+"#,
+        );
+}
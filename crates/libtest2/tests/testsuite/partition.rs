@@ -0,0 +1,138 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(a, b, c, d, e);
+
+fn a(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn b(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn c(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn d(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn e(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--partition", "count:1/2"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--partition` requires `-Zunstable-options`
+"#,
+        );
+}
+
+#[test]
+fn invalid() {
+    test_cmd()
+        .args(["-Zunstable-options", "--partition", "count:3"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--partition` expects `count:INDEX/TOTAL` or `hash:INDEX/TOTAL`, got `count:3`
+"#,
+        );
+}
+
+#[test]
+fn out_of_range() {
+    test_cmd()
+        .args(["-Zunstable-options", "--partition", "hash:0/2"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--partition` index must be between 1 and 2, got 0
+"#,
+        );
+}
+
+#[test]
+fn count() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--partition",
+            "count:2/2",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 3 tests
+test c ... ok
+test d ... ok
+test e ... ok
+
+test result: ok. 3 passed; 0 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn count_after_filter() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--partition",
+            "count:1/2",
+            "--skip",
+            "a",
+            "--list",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"b: test
+c: test
+
+2 tests
+
+"#,
+        );
+}
+
+#[test]
+fn hash() {
+    test_cmd()
+        .args(["-Zunstable-options", "--partition", "hash:1/2", "--list"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"a: test
+c: test
+e: test
+
+3 tests
+
+"#,
+        );
+}
@@ -0,0 +1,121 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(flaky, steady);
+
+fn flaky(state: &libtest2::State) -> libtest2::RunResult {
+    libtest2::ensure!(state.repetition() != 2, "failed on run {}", state.repetition());
+    Ok(())
+}
+
+fn steady(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+#[test]
+fn runs_are_reported_separately() {
+    test_cmd()
+        .args(["-Zunstable-options", "--repeat", "3", "--test-threads", "1"])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 6 tests
+test flaky #1  ... ok
+test flaky #2  ... FAILED
+test flaky #3  ... ok
+test steady #1 ... ok
+test steady #2 ... ok
+test steady #3 ... ok
+
+failures:
+
+---- flaky #2 ----
+test.rs:5: failed on run 2
+
+
+failures:
+    flaky #2
+
+test result: FAILED. 5 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn in_subprocess() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--repeat",
+            "3",
+            "--run-in-subprocess",
+            "--test-threads",
+            "1",
+        ])
+        .assert()
+        .code(101)
+        .stdout_matches(
+            r#"
+running 6 tests
+test flaky #1  ... ok
+test flaky #2  ... FAILED
+test flaky #3  ... ok
+test steady #1 ... ok
+test steady #2 ... ok
+test steady #3 ... ok
+
+failures:
+
+---- flaky #2 ----
+test.rs:5: failed on run 2
+
+
+failures:
+    flaky #2
+
+test result: FAILED. 5 passed; 1 failed; 0 ignored; 0 filtered out; finished in [..]s
+
+"#,
+        );
+}
+
+#[test]
+fn list_is_not_repeated() {
+    test_cmd()
+        .args(["-Zunstable-options", "--repeat", "3", "--list"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"flaky: test
+steady: test
+
+2 tests
+
+"#,
+        );
+}
+
+#[test]
+fn requires_unstable() {
+    test_cmd()
+        .args(["--repeat", "3"])
+        .assert()
+        .code(1)
+        .stdout_matches("")
+        .stderr_matches(
+            r#"`--repeat` requires `-Zunstable-options`
+"#,
+        );
+}
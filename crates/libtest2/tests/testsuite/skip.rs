@@ -0,0 +1,191 @@
+fn test_cmd() -> snapbox::cmd::Command {
+    static BIN: once_cell::sync::Lazy<(std::path::PathBuf, std::path::PathBuf)> =
+        once_cell::sync::Lazy::new(|| {
+            let package_root = crate::util::new_test(
+                r#"
+libtest2::libtest2_main!(passes, needs_gpu, slow);
+
+fn passes(_state: &libtest2::State) -> libtest2::RunResult {
+    Ok(())
+}
+
+fn needs_gpu(state: &libtest2::State) -> libtest2::RunResult {
+    state.skip("no gpu available")?;
+    Ok(())
+}
+
+fn slow(state: &libtest2::State) -> libtest2::RunResult {
+    state.ignore_for("slow")?;
+    Ok(())
+}
+"#,
+                false,
+            );
+            let bin = crate::util::compile_test(&package_root);
+            (bin, package_root)
+        });
+    crate::util::test_bin(&BIN.0, &BIN.1)
+}
+
+fn check(args: &[&str], code: i32, single: &str, parallel: &str) {
+    test_cmd()
+        .args(args)
+        .args(["--test-threads", "1"])
+        .assert()
+        .code(code)
+        .stdout_matches(single);
+    test_cmd()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout_matches(parallel);
+}
+
+#[test]
+fn normal() {
+    check(
+        &[],
+        0,
+        r#"
+running 3 tests
+test needs_gpu ... skipped (no gpu available)
+test passes    ... ok
+test slow      ... ignored, slow
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 1 skipped; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+test result: ok. 1 passed; 0 failed; 1 ignored; 1 skipped; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+fn include_ignored() {
+    check(
+        &["--include-ignored"],
+        0,
+        r#"
+running 3 tests
+test needs_gpu ... skipped (no gpu available)
+test passes    ... ok
+test slow      ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 1 skipped; 0 filtered out; finished in [..]s
+
+"#,
+        r#"
+running 3 tests
+...
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 1 skipped; 0 filtered out; finished in [..]s
+
+"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json() {
+    test_cmd()
+        .args(["-Zunstable-options", "--format=json", "--test-threads=1", "needs_gpu"])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"needs_gpu","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"passes","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"slow","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":2,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"needs_gpu","suite":"[..]"}
+{"event":"case-complete","name":"needs_gpu","mode":"test","status":"skipped","message":"no gpu available","elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#,
+        );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_quiet() {
+    let expected = r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":2,"suite":"[..]"}
+{"event":"suite-start","suite":"[..]"}
+{"event":"case-start","name":"needs_gpu","suite":"[..]"}
+{"event":"case-complete","name":"needs_gpu","mode":"test","status":"skipped","message":"no gpu available","elapsed_s":"[..]","suite":"[..]"}
+{"event":"suite-complete","elapsed_s":"[..]","suite":"[..]"}
+"#;
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--quiet",
+            "--test-threads=1",
+            "needs_gpu",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(expected);
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--quiet",
+            "--format=json",
+            "--test-threads=1",
+            "needs_gpu",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(expected);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn list_json_quiet() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--format=json",
+            "--quiet",
+            "--list",
+            "needs_gpu",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"{"event":"discover-start","suite":"[..]"}
+{"event":"discover-case","name":"needs_gpu","mode":"test","run":true,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"passes","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-case","name":"slow","mode":"test","run":false,"ignored":false,"suite":"[..]"}
+{"event":"discover-complete","elapsed_s":"[..]","seed":null,"num_to_run":1,"num_filtered_out":2,"suite":"[..]"}
+"#,
+        );
+}
+
+#[test]
+fn quiet_then_pretty() {
+    test_cmd()
+        .args([
+            "-Zunstable-options",
+            "--quiet",
+            "--format=pretty",
+            "--test-threads=1",
+            "passes",
+        ])
+        .assert()
+        .code(0)
+        .stdout_matches(
+            r#"
+running 1 test
+test passes ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 2 filtered out; finished in [..]s
+
+"#,
+        );
+}
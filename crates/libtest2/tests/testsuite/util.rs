@@ -50,6 +50,13 @@ pub fn compile_test(package_root: &std::path::Path) -> std::path::PathBuf {
     tests::compile_test(&manifest_path, target_name, args)
 }
 
+/// Run the test binary from [`compile_test`], without the color settings of the calling `cargo`
+pub fn test_bin(bin: &std::path::Path, package_root: &std::path::Path) -> snapbox::cmd::Command {
+    snapbox::cmd::Command::new(bin)
+        .current_dir(package_root)
+        .env_remove("CARGO_TERM_COLOR")
+}
+
 fn mimic_relpath(root: &std::path::Path) -> std::path::PathBuf {
     let current_dir = std::env::current_dir().unwrap();
     let relpath = pathdiff::diff_paths(current_dir, root).unwrap();